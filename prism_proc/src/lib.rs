@@ -48,7 +48,34 @@ impl ChildType {
     }
 }
 
-#[proc_macro_derive(Component, attributes(skip))]
+/// Returns the field marked `#[layout]`, or the first field when none is, followed by the children in order.
+///
+/// Children are every other field not marked `#[skip]`, sorted by their `#[order(n)]` index where given and
+/// their declaration position otherwise.
+fn split_fields(fields: Vec<(TokenTree, &Field)>, has_tag: impl Fn(&Field, &str) -> bool) -> (TokenTree, Vec<ChildType>) {
+    if fields.is_empty() {panic!("Component requires a layout field, the first field or one marked #[layout]");}
+    if fields.iter().filter(|(_, field)| has_tag(field, "layout")).count() > 1 {panic!("Component allows only one field marked #[layout]");}
+    let layout = fields.iter().position(|(_, field)| has_tag(field, "layout")).unwrap_or(0);
+
+    let mut children: Vec<(usize, ChildType)> = fields.iter().enumerate()
+        .filter(|(index, (_, field))| *index != layout && !has_tag(field, "skip"))
+        .map(|(index, (ident, field))| {
+            let order = field.attrs.iter().find(|attr| attr.path().is_ident("order")).map(|attr| {
+                attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse::<usize>())
+                    .unwrap_or_else(|_| panic!("#[order] takes a child index such as #[order(0)]"))
+            });
+            (order.unwrap_or(index), ChildType::from_field(&field.ty, ident.clone()))
+        }).collect();
+    children.sort_by_key(|(order, _)| *order);
+    (fields[layout].0.clone(), children.into_iter().map(|(_, child)| child).collect())
+}
+
+/// Derives `Component` for a struct or enum of named variants.
+///
+/// The layout is the field marked `#[layout]`, or the first field when none is marked. Every other field is a
+/// child unless marked `#[skip]`, and children are laid out in declaration order unless given an explicit
+/// position with `#[order(n)]`.
+#[proc_macro_derive(Component, attributes(skip, layout, order))]
 pub fn derive_component(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
@@ -58,26 +85,13 @@ pub fn derive_component(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 
     match input.data {
         Data::Struct(struc) => {
-            let (layout, children): (TokenTree, Vec<ChildType>) = match struc.fields {
-                Fields::Named(named) => {
-                    let mut iterator = named.named.into_iter();
-                    (
-                        TokenTree::Ident(iterator.next().map(|f| f.ident).flatten().unwrap_or_else(|| {panic!("Component requires the first field of the structure to be the layout");})),
-                        iterator.flat_map(|field|
-                            (!has_tag(&field, "skip")).then(|| ChildType::from_field(&field.ty, TokenTree::Ident(field.ident.unwrap())))
-                        ).collect()
-                    )
-                },
-                Fields::Unnamed(unnamed) => {
-                    let mut iterator = unnamed.unnamed.iter().enumerate();
-                    iterator.next().unwrap_or_else(|| {panic!("Component requires the first field of the structure to be the layout");});
-                    (
-                        TokenTree::Literal(Literal::usize_unsuffixed(0)),
-                        iterator.flat_map(|(index, field)|
-                            (!has_tag(&field, "skip")).then(|| ChildType::from_field(&field.ty, TokenTree::Literal(Literal::usize_unsuffixed(index))))
-                        ).collect()
-                    )
-                },
+            let (layout, children): (TokenTree, Vec<ChildType>) = match &struc.fields {
+                Fields::Named(named) => split_fields(
+                    named.named.iter().map(|field| (TokenTree::Ident(field.ident.clone().unwrap()), field)).collect(), has_tag
+                ),
+                Fields::Unnamed(unnamed) => split_fields(
+                    unnamed.unnamed.iter().enumerate().map(|(index, field)| (TokenTree::Literal(Literal::usize_unsuffixed(index)), field)).collect(), has_tag
+                ),
                 Fields::Unit => panic!("Component requires the first field of the structure to be the layout")
             };
             children.is_empty().then(|| {panic!("Component requires at least one child component in the structure");});
@@ -136,12 +150,9 @@ pub fn derive_component(input: proc_macro::TokenStream) -> proc_macro::TokenStre
                     _ => panic!("Only named enum variants are supported"),
                 };
 
-                let mut iter = fields.iter();
-                let layout_ident = iter.next().unwrap().ident.as_ref().unwrap();
-                let children: Vec<ChildType> = iter.filter(|f| !has_tag(f, "skip")).map(|f| {
-                    let ident = f.ident.as_ref().unwrap();
-                    ChildType::from_field(&f.ty, TokenTree::Ident(ident.clone()))
-                }).collect();
+                let (layout_ident, children) = split_fields(
+                    fields.iter().map(|field| (TokenTree::Ident(field.ident.clone().unwrap()), field)).collect(), has_tag
+                );
 
                 (name, layout_ident, children)
             });
@@ -215,3 +226,63 @@ pub fn derive_component(input: proc_macro::TokenStream) -> proc_macro::TokenStre
         Data::Union(_) => {panic!("Cannot implement Component for a Union")}
     }
 }
+
+/// Derives `Event` with one of the standard ways of passing an event on to a drawable's children.
+///
+/// The strategy is chosen with `#[event(..)]` on the type:
+/// - `broadcast`, the default, clones the event to every child.
+/// - `positional` sends it whole to the topmost child under its position, made relative to that child, and to
+///   the rest with no position, like `MouseEvent`. The position is the `Option<(f32, f32)>` field named
+///   `position` or marked `#[position]`.
+/// - `focused` broadcasts it while something has focus, and every `Focusable` without focus drops it.
+/// - `none` passes it to no children.
+///
+/// Every strategy but `none` needs the event to be `Clone`.
+#[proc_macro_derive(Event, attributes(event, position))]
+pub fn derive_event(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let strategy = input.attrs.iter().find(|attr| attr.path().is_ident("event")).map(|attr| {
+        attr.parse_args::<syn::Ident>().unwrap_or_else(|_| panic!("#[event] takes a strategy: broadcast, positional, focused or none")).to_string()
+    }).unwrap_or("broadcast".to_string());
+
+    let (pass, focused_only) = match strategy.as_str() {
+        "broadcast" => (quote!{prism::event::broadcast(self, children)}, false),
+        "focused" => (quote!{prism::event::to_focused(self, ctx, children)}, true),
+        "none" => (quote!{prism::event::absorb(children)}, false),
+        "positional" => {
+            let fields = match &input.data {
+                Data::Struct(struc) => &struc.fields,
+                _ => panic!("A positional Event must be a struct with a position field"),
+            };
+            let position = fields.iter().enumerate().find(|(_, field)| field.attrs.iter().any(|attr| attr.path().is_ident("position")))
+                .or_else(|| fields.iter().enumerate().find(|(_, field)| field.ident.as_ref().is_some_and(|ident| ident == "position")))
+                .map(|(index, field)| match &field.ident {
+                    Some(ident) => TokenTree::Ident(ident.clone()),
+                    None => TokenTree::Literal(Literal::usize_unsuffixed(index)),
+                })
+                .unwrap_or_else(|| panic!("A positional Event needs a field named position or marked #[position]"));
+            (quote!{
+                prism::event::positions(self.#position, children).into_iter().map(|position| {
+                    let mut event = (*self).clone();
+                    event.#position = position;
+                    Some(Box::new(event) as Box<dyn prism::event::Event>)
+                }).collect()
+            }, false)
+        },
+        other => panic!("Unknown Event strategy {other}, expected broadcast, positional, focused or none"),
+    };
+    let ctx = if focused_only {quote!{ctx}} else {quote!{_ctx}};
+    let focused_only = focused_only.then(|| quote!{fn focused_only(&self) -> bool {true}});
+
+    proc_macro::TokenStream::from(quote!{
+        impl #impl_generics prism::event::Event for #name #ty_generics #where_clause {
+            fn pass(self: Box<Self>, #ctx: &mut prism::Context, children: &[prism::layout::Area]) -> Vec<Option<Box<dyn prism::event::Event>>> {
+                #pass
+            }
+            #focused_only
+        }
+    })
+}
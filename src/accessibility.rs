@@ -0,0 +1,97 @@
+use crate::canvas::Instruction;
+use crate::drawable::{Drawable, RequestTree, SizedTree, Size, Offset, Rect};
+use crate::event::Event;
+use crate::Context;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Role {
+    #[default]
+    Group,
+    Button,
+    Text,
+    Heading,
+    Image,
+    TextInput,
+    Slider,
+    Checkbox,
+    Link,
+    List,
+    ListItem,
+}
+
+/// Something assistive technologies can ask a node to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Press,
+    Focus,
+    ScrollForward,
+    ScrollBackward,
+}
+
+/// The description of a drawable given to assistive technologies.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Semantics {
+    pub role: Role,
+    pub label: Option<String>,
+    /// The current value of an input, such as a slider's position or a text field's contents.
+    pub value: Option<String>,
+    pub actions: Vec<Action>,
+}
+
+/// A node of the accessibility tree returned by [`Instance::accessibility`](crate::Instance::accessibility),
+/// mirroring the parts of the [`SizedTree`] that describe themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    pub semantics: Semantics,
+    /// The node's area on screen as `(x, y, width, height)`.
+    pub bounds: Rect,
+    pub children: Vec<AccessNode>,
+}
+
+/// A wrapper that describes its child to assistive technologies, with any described descendants as children.
+///
+///```rust
+/// Semantic::new(Role::Button, "Send", Button::new(icon)).action(Action::Press)
+///```
+#[derive(Debug, Clone)]
+pub struct Semantic<D: Drawable + Clone>(pub Semantics, pub D);
+
+impl<D: Drawable + Clone> Semantic<D> {
+    pub fn new(role: Role, label: impl Into<String>, child: D) -> Self {
+        Semantic(Semantics{role, label: Some(label.into()), ..Semantics::default()}, child)
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.0.value = Some(value.into());
+        self
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.0.actions.push(action);
+        self
+    }
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+}
+
+impl<D: Drawable + Clone + 'static> Drawable for Semantic<D> {
+    fn request_size(&self) -> RequestTree {self.1.request_size()}
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.1.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {self.1.draw(sized, offset, bound)}
+
+    fn name(&self) -> String {self.1.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {self.1.event(ctx, sized, event)}
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        vec![AccessNode{
+            semantics: self.0.clone(),
+            bounds: (offset.0, offset.1, sized.0.0, sized.0.1),
+            children: self.1.accessibility(sized, offset)
+        }]
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.1)]}
+}
@@ -0,0 +1,110 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use crate::canvas::{Color, Instruction};
+use crate::drawable::{Drawable, RequestTree, SizedTree, Size, Offset, Rect};
+use crate::event::Event;
+use crate::accessibility::AccessNode;
+use crate::Context;
+
+thread_local! {
+    static VALUES: RefCell<Vec<(TypeId, Arc<dyn Any>)>> = const {RefCell::new(Vec::new())};
+}
+
+/// Returns the ambient value of type `T` set by the closest enclosing [`Ambient`].
+///
+/// Only meaningful while the tree is being sized, built, drawn or handling an event, which is when
+/// every [`Ambient`] above the caller has its values in scope.
+pub fn get<T: Clone + 'static>() -> Option<T> {
+    VALUES.with_borrow(|values| values.iter().rev()
+        .find(|(id, _)| *id == TypeId::of::<T>())
+        .and_then(|(_, value)| value.downcast_ref::<T>().cloned())
+    )
+}
+
+/// The default text color for descendants, for text drawables to fall back on.
+#[derive(Debug, Clone, Copy)]
+pub struct TextColor(pub Color);
+
+/// Whether descendants are disabled, for emitters to ignore input and drawables to dim themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Disabled(pub bool);
+
+struct Scope(usize);
+
+impl Scope {
+    fn enter(values: &[(TypeId, Arc<dyn Any>)]) -> Self {
+        VALUES.with_borrow_mut(|stack| stack.extend(values.iter().cloned()));
+        Scope(values.len())
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        VALUES.with_borrow_mut(|stack| stack.truncate(stack.len() - self.0));
+    }
+}
+
+/// A wrapper that makes values available to every descendant through [`get`], like an environment,
+/// so styling and flags don't need threading through every constructor.
+///
+///```rust
+/// Ambient::new(form).with(Disabled(true)).with(TextColor(theme.colors.text_secondary))
+///```
+#[derive(Clone)]
+pub struct Ambient<D: Drawable + Clone>(Vec<(TypeId, Arc<dyn Any>)>, pub D);
+
+impl<D: Drawable + Clone> Ambient<D> {
+    pub fn new(child: D) -> Self {Ambient(Vec::new(), child)}
+
+    /// Sets `value` for every descendant, replacing any value of the same type set by this wrapper.
+    pub fn with<T: Clone + 'static>(mut self, value: T) -> Self {
+        self.set(value);
+        self
+    }
+
+    pub fn set<T: Clone + 'static>(&mut self, value: T) {
+        self.0.retain(|(id, _)| *id != TypeId::of::<T>());
+        self.0.push((TypeId::of::<T>(), Arc::new(value)));
+    }
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+}
+
+impl<D: Drawable + Clone> std::fmt::Debug for Ambient<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Ambient").field(&self.0.len()).field(&self.1).finish()
+    }
+}
+
+impl<D: Drawable + Clone + 'static> Drawable for Ambient<D> {
+    fn request_size(&self) -> RequestTree {
+        let _scope = Scope::enter(&self.0);
+        self.1.request_size()
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
+        let _scope = Scope::enter(&self.0);
+        self.1.build(size, request)
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let _scope = Scope::enter(&self.0);
+        self.1.draw(sized, offset, bound)
+    }
+
+    fn name(&self) -> String {self.1.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        let _scope = Scope::enter(&self.0);
+        self.1.event(ctx, sized, event)
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        let _scope = Scope::enter(&self.0);
+        self.1.accessibility(sized, offset)
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.1)]}
+}
@@ -0,0 +1,486 @@
+use std::time::Duration;
+
+use crate::canvas::{Color, Instruction};
+use crate::drawable::{Drawable, RequestTree, SizedTree, Size, Offset, Rect};
+use crate::event::{Event, TickEvent};
+use crate::accessibility::AccessNode;
+use crate::Context;
+
+/// A value that can be interpolated, `t` running from `0.0` at `self` to `1.0` at `to`.
+///
+/// `t` may leave that range, such as when a spring overshoots its target.
+pub trait Lerp: Clone {
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {self + (to - self) * t}
+}
+
+impl Lerp for (f32, f32) {
+    fn lerp(&self, to: &Self, t: f32) -> Self {(self.0.lerp(&to.0, t), self.1.lerp(&to.1, t))}
+}
+
+impl Lerp for (f32, f32, f32, f32) {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        (self.0.lerp(&to.0, t), self.1.lerp(&to.1, t), self.2.lerp(&to.2, t), self.3.lerp(&to.3, t))
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let channel = |a: u8, b: u8| (a as f32).lerp(&(b as f32), t).round().clamp(0.0, 255.0) as u8;
+        Color(channel(self.0, to.0), channel(self.1, to.1), channel(self.2, to.2), channel(self.3, to.3))
+    }
+}
+
+/// How a tween's progress accelerates over its duration.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+    /// A CSS style cubic bezier through `(0, 0)`, the control points `(x1, y1)` and `(x2, y2)`, and `(1, 1)`.
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// Maps linear progress `t` in `0.0..=1.0` to eased progress.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::EaseIn => bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::CubicBezier(x1, y1, x2, y2) => bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+fn bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let curve = |a: f32, b: f32, t: f32| 3.0 * (1.0 - t).powi(2) * t * a + 3.0 * (1.0 - t) * t.powi(2) * b + t.powi(3);
+    let slope = |a: f32, b: f32, t: f32| 3.0 * (1.0 - t).powi(2) * a + 6.0 * (1.0 - t) * t * (b - a) + 3.0 * t.powi(2) * (1.0 - b);
+
+    let mut t = x;
+    for _ in 0..8 {
+        let error = curve(x1, x2, t) - x;
+        let slope = slope(x1, x2, t);
+        if error.abs() < 1e-5 || slope.abs() < 1e-6 {break;}
+        t = (t - error / slope).clamp(0.0, 1.0);
+    }
+    curve(y1, y2, t)
+}
+
+/// A damped spring pulling a value towards its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {Spring{stiffness: 170.0, damping: 26.0, mass: 1.0}}
+}
+
+const SPRING_STEP: f32 = 1.0 / 240.0;
+const SPRING_REST: f32 = 0.001;
+
+impl Spring {
+    /// Advances `progress` towards `1.0` by `delta` seconds, returning whether it is still moving.
+    fn step(&self, progress: &mut f32, velocity: &mut f32, delta: f32) -> bool {
+        let delta = delta.min(1.0);
+        let steps = (delta / SPRING_STEP).ceil().max(1.0);
+        let h = delta / steps;
+        for _ in 0..steps as u32 {
+            let force = -self.stiffness * (*progress - 1.0) - self.damping * *velocity;
+            *velocity += force / self.mass.max(f32::EPSILON) * h;
+            *progress += *velocity * h;
+        }
+        (1.0 - *progress).abs() > SPRING_REST || velocity.abs() > SPRING_REST
+    }
+}
+
+/// How an [`Animated`] value moves to a new target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// Moves over a fixed duration following an easing curve.
+    Tween(Duration, Easing),
+    /// Moves under spring physics, settling whenever it comes to rest.
+    Spring(Spring),
+}
+
+impl Default for Curve {
+    fn default() -> Self {Curve::Tween(Duration::from_millis(200), Easing::EaseInOut)}
+}
+
+/// A value that animates towards its target as it is ticked.
+///
+/// Components keep one in a `#[skip]` field, tick it with [`Context::delta`](crate::Context::delta) on every [`TickEvent`](crate::event::TickEvent)
+/// and apply [`Animated::get`] to their layout, [`Opacity`](crate::drawable::Opacity) or colors whenever [`Animated::tick`] reports a change,
+/// asking for the next frame with [`Context::request_frame`](crate::Context::request_frame) while it is still running.
+///
+///```rust
+/// if event.downcast_ref::<TickEvent>().is_some() && self.fade.tick(ctx.delta()) {
+///     self.1.0 = *self.fade.get();
+///     if self.fade.is_running() {ctx.request_frame();}
+/// }
+///```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animated<T: Lerp> {
+    from: T,
+    to: T,
+    value: T,
+    curve: Curve,
+    elapsed: Duration,
+    progress: f32,
+    velocity: f32,
+    running: bool,
+    essential: bool,
+}
+
+impl<T: Lerp> Animated<T> {
+    pub fn new(value: T, curve: Curve) -> Self {
+        Animated{from: value.clone(), to: value.clone(), value, curve, elapsed: Duration::ZERO, progress: 1.0, velocity: 0.0, running: false, essential: false}
+    }
+
+    /// Keeps animating in low power mode instead of jumping to the target, for motion that carries meaning
+    /// such as a progress indicator.
+    pub fn essential(mut self) -> Self {
+        self.essential = true;
+        self
+    }
+
+    pub fn get(&self) -> &T {&self.value}
+    pub fn target(&self) -> &T {&self.to}
+    pub fn curve(&self) -> Curve {self.curve}
+    pub fn is_running(&self) -> bool {self.running}
+
+    /// Changes the curve used by the next call to [`Animated::animate_to`].
+    pub fn set_curve(&mut self, curve: Curve) {self.curve = curve;}
+
+    /// Starts animating from the current value to `target`. When already running this retargets the animation,
+    /// and a spring keeps its velocity so the motion doesn't jerk.
+    pub fn animate_to(&mut self, target: T) {
+        self.from = self.value.clone();
+        self.to = target;
+        self.elapsed = Duration::ZERO;
+        self.progress = 0.0;
+        if !self.running {self.velocity = 0.0;}
+        self.running = true;
+    }
+
+    /// Jumps to `value`, cancelling any running animation.
+    pub fn set(&mut self, value: T) {
+        *self = Animated{essential: self.essential, ..Animated::new(value, self.curve)};
+    }
+
+    /// Stops any running animation at its current value.
+    pub fn cancel(&mut self) {self.set(self.value.clone());}
+
+    /// Advances the animation by `delta`, returning whether the value changed.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        if !self.running {return false;}
+        if !self.essential && crate::power::is_low_power() {
+            self.set(self.to.clone());
+            return true;
+        }
+        match self.curve {
+            Curve::Tween(duration, easing) => {
+                self.elapsed += delta;
+                let t = match duration.is_zero() {
+                    true => 1.0,
+                    false => (self.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+                };
+                self.progress = easing.apply(t);
+                self.running = t < 1.0;
+            },
+            Curve::Spring(spring) => {
+                self.running = spring.step(&mut self.progress, &mut self.velocity, delta.as_secs_f32());
+            }
+        }
+        self.value = match self.running {
+            true => self.from.lerp(&self.to, self.progress),
+            false => self.to.clone()
+        };
+        true
+    }
+}
+
+/// A wrapper that animates a value on its child without the child handling [`TickEvent`]s itself.
+///
+/// It ticks its [`Animated`] value every frame, hands each new value to `apply` along with the child, and keeps
+/// asking for frames until the value comes to rest.
+///
+///```rust
+/// let mut badge = Animator::new(Opacity(0.0, badge), 0.0, Curve::default(), |badge, alpha| badge.0 = *alpha);
+/// badge.animate_to(1.0);
+///```
+#[derive(Debug, Clone)]
+pub struct Animator<D: Drawable + Clone, T: Lerp>(pub D, Animated<T>, fn(&mut D, &T));
+
+impl<D: Drawable + Clone, T: Lerp> Animator<D, T> {
+    /// Wraps `child`, applying `value` to it right away.
+    pub fn new(mut child: D, value: T, curve: Curve, apply: fn(&mut D, &T)) -> Self {
+        apply(&mut child, &value);
+        Animator(child, Animated::new(value, curve), apply)
+    }
+
+    pub fn get(&self) -> &T {self.1.get()}
+    pub fn is_running(&self) -> bool {self.1.is_running()}
+    pub fn inner(&mut self) -> &mut D {&mut self.0}
+
+    /// Animates towards `target`, starting on the next frame.
+    pub fn animate_to(&mut self, target: T) {self.1.animate_to(target)}
+
+    /// Jumps to `value`, applying it right away.
+    pub fn set(&mut self, value: T) {
+        self.1.set(value);
+        (self.2)(&mut self.0, self.1.get());
+    }
+
+    /// Lets the animation keep running in low power mode, see [`Animated::essential`].
+    pub fn essential(mut self) -> Self {
+        self.1 = self.1.essential();
+        self
+    }
+}
+
+impl<D: Drawable + Clone + 'static, T: Lerp + std::fmt::Debug + 'static> Drawable for Animator<D, T> {
+    fn request_size(&self) -> RequestTree {self.0.request_size()}
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.0.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {self.0.draw(sized, offset, bound)}
+
+    fn name(&self) -> String {self.0.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        if event.is::<TickEvent>() && self.1.tick(ctx.delta()) {
+            (self.2)(&mut self.0, self.1.get());
+            if self.1.is_running() {ctx.request_frame();}
+        }
+        self.0.event(ctx, sized, event)
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {self.0.accessibility(sized, offset)}
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.0)]}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Keyframe<T> {
+    at: Duration,
+    value: T,
+    easing: Easing,
+}
+
+/// The keyframes of one property in a [`Timeline`].
+///
+///```rust
+/// let fade = Track::new(0.0).to(Duration::from_millis(300), 1.0, Easing::EaseOut);
+///```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T: Lerp> {
+    keyframes: Vec<Keyframe<T>>,
+    delay: Duration,
+}
+
+impl<T: Lerp> Track<T> {
+    /// Creates a track holding `value` until its first keyframe.
+    pub fn new(value: T) -> Self {
+        Track{keyframes: vec![Keyframe{at: Duration::ZERO, value, easing: Easing::Linear}], delay: Duration::ZERO}
+    }
+
+    /// Adds a keyframe reaching `value` at `at` after the track starts, eased from the keyframe before it.
+    pub fn to(mut self, at: Duration, value: T, easing: Easing) -> Self {
+        let index = self.keyframes.partition_point(|keyframe| keyframe.at <= at);
+        self.keyframes.insert(index, Keyframe{at, value, easing});
+        self
+    }
+
+    /// Starts the track `delay` after the timeline does.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn duration(&self) -> Duration {self.delay + self.keyframes.last().map(|keyframe| keyframe.at).unwrap_or_default()}
+
+    /// Returns the value `time` after the timeline started.
+    pub fn sample(&self, time: Duration) -> T {
+        let time = time.saturating_sub(self.delay);
+        let next = self.keyframes.partition_point(|keyframe| keyframe.at <= time);
+        match (next.checked_sub(1).map(|i| &self.keyframes[i]), self.keyframes.get(next)) {
+            (Some(from), Some(to)) => {
+                let span = (to.at - from.at).as_secs_f32();
+                let t = if span > 0.0 {(time - from.at).as_secs_f32() / span} else {1.0};
+                from.value.lerp(&to.value, to.easing.apply(t))
+            },
+            (Some(last), None) => last.value.clone(),
+            (None, Some(first)) => first.value.clone(),
+            (None, None) => unreachable!("a track always has a keyframe"),
+        }
+    }
+}
+
+trait Sampled: std::any::Any + std::fmt::Debug {
+    fn duration(&self) -> Duration;
+    fn sample_any(&self, time: Duration) -> Box<dyn std::any::Any>;
+}
+
+impl<T: Lerp + std::fmt::Debug + 'static> Sampled for Track<T> {
+    fn duration(&self) -> Duration {Track::duration(self)}
+    fn sample_any(&self, time: Duration) -> Box<dyn std::any::Any> {Box::new(self.sample(time))}
+}
+
+/// How many times a [`Timeline`] plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repeat {
+    #[default]
+    Once,
+    Times(u32),
+    Forever,
+}
+
+/// A sequence of keyframed [`Track`]s, one per animated property, played together.
+///
+/// Ticked like an [`Animated`] value, it broadcasts an [`event::TimelineFinished`](crate::event::TimelineFinished)
+/// carrying its [`Timeline::id`] when it completes.
+///
+///```rust
+/// let mut intro = Timeline::new()
+///     .track("opacity", Track::new(0.0).to(Duration::from_millis(300), 1.0, Easing::EaseOut))
+///     .stagger("item", 5, Track::new((0.0, 20.0)).to(Duration::from_millis(300), (0.0, 0.0), Easing::EaseOut), Duration::from_millis(50));
+/// intro.play();
+///
+/// if intro.tick(ctx) {
+///     self.1.0 = intro.value::<f32>("opacity").unwrap();
+///     let offset = intro.staggered::<(f32, f32)>("item", 2);
+/// }
+///```
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    id: uuid::Uuid,
+    tracks: Vec<(&'static str, usize, std::sync::Arc<dyn Sampled>)>,
+    repeat: Repeat,
+    alternate: bool,
+    elapsed: Duration,
+    running: bool,
+    essential: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {Timeline::new()}
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Timeline{id: uuid::Uuid::new_v4(), tracks: Vec::new(), repeat: Repeat::Once, alternate: false, elapsed: Duration::ZERO, running: false, essential: false}
+    }
+
+    pub fn track<T: Lerp + std::fmt::Debug + 'static>(mut self, name: &'static str, track: Track<T>) -> Self {
+        self.tracks.push((name, 0, std::sync::Arc::new(track)));
+        self
+    }
+
+    /// Adds `count` copies of `track` under `name`, each starting `step` after the one before.
+    pub fn stagger<T: Lerp + std::fmt::Debug + 'static>(mut self, name: &'static str, count: usize, track: Track<T>, step: Duration) -> Self {
+        for index in 0..count {
+            let delay = track.delay + step * index as u32;
+            self.tracks.push((name, index, std::sync::Arc::new(track.clone().delay(delay))));
+        }
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Plays every other repetition backwards.
+    pub fn alternate(mut self, alternate: bool) -> Self {
+        self.alternate = alternate;
+        self
+    }
+
+    pub fn id(&self) -> uuid::Uuid {self.id}
+    pub fn is_running(&self) -> bool {self.running}
+
+    /// The length of one repetition, until the last track finishes.
+    pub fn duration(&self) -> Duration {self.tracks.iter().map(|(_, _, track)| track.duration()).max().unwrap_or_default()}
+
+    /// Plays from the start.
+    pub fn play(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.running = true;
+    }
+
+    /// Pauses at the current point, [`Timeline::resume`] continuing from there.
+    pub fn pause(&mut self) {self.running = false;}
+    pub fn resume(&mut self) {self.running = true;}
+
+    /// Stops and jumps back to the start.
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Keeps playing in low power mode instead of pausing.
+    pub fn essential(mut self) -> Self {
+        self.essential = true;
+        self
+    }
+
+    /// Advances by [`Context::delta`](crate::Context::delta), returning whether any value changed.
+    ///
+    /// In low power mode a timeline not marked [`Timeline::essential`] holds still until the mode ends.
+    pub fn tick(&mut self, ctx: &mut crate::Context) -> bool {
+        if !self.running || (!self.essential && crate::power::is_low_power()) {return false;}
+        self.elapsed += ctx.delta();
+        let duration = self.duration();
+        let finished = match self.repeat {
+            Repeat::Once => Some(duration),
+            Repeat::Times(count) => Some(duration * count.max(1)),
+            Repeat::Forever => None,
+        }.filter(|end| self.elapsed >= *end);
+        if let Some(end) = finished {
+            self.elapsed = end;
+            self.running = false;
+            ctx.emit(crate::event::TimelineFinished(self.id));
+        } else {
+            ctx.request_frame();
+        }
+        true
+    }
+
+    /// Returns the value of the track added under `name`, `None` if there is none holding a `T`.
+    pub fn value<T: 'static>(&self, name: &'static str) -> Option<T> {self.staggered(name, 0)}
+
+    /// Returns the value of the `index`th track added with [`Timeline::stagger`] under `name`.
+    pub fn staggered<T: 'static>(&self, name: &'static str, index: usize) -> Option<T> {
+        let time = self.local_time();
+        self.tracks.iter()
+            .find(|(n, i, _)| *n == name && *i == index)
+            .and_then(|(_, _, track)| track.sample_any(time).downcast().ok())
+            .map(|value| *value)
+    }
+
+    fn local_time(&self) -> Duration {
+        let duration = self.duration();
+        if duration.is_zero() {return Duration::ZERO;}
+        let cycle = (self.elapsed.as_secs_f64() / duration.as_secs_f64()).floor() as u32;
+        let at_end = cycle > 0 && self.elapsed == duration * cycle;
+        let (cycle, time) = match at_end {
+            true => (cycle - 1, duration),
+            false => (cycle, self.elapsed - duration * cycle)
+        };
+        match self.alternate && cycle % 2 == 1 {
+            true => duration - time,
+            false => time
+        }
+    }
+}
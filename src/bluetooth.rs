@@ -0,0 +1,24 @@
+use uuid::Uuid;
+
+/// A Bluetooth LE peripheral, identified by the platform assigned id reported in its [`Advertisement`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Device(pub String);
+
+/// A characteristic of a connected [`Device`], addressed by its service and characteristic UUIDs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Characteristic {
+    pub device: Device,
+    pub service: Uuid,
+    pub characteristic: Uuid,
+}
+
+/// A peripheral seen while scanning with [`Context::ble_scan`](crate::Context::ble_scan).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Advertisement {
+    pub device: Device,
+    pub name: Option<String>,
+    /// The signal strength in dBm.
+    pub rssi: i16,
+    pub services: Vec<Uuid>,
+    pub manufacturer_data: Vec<u8>,
+}
@@ -0,0 +1,112 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::canvas::Instruction;
+use crate::drawable::{Drawable, RequestTree, SizedTree, Offset, Rect};
+use crate::event::Event;
+use crate::layout::{Area, SizeRequest};
+use crate::Context;
+
+thread_local! {
+    static SHOWN: Cell<bool> = const {Cell::new(false)};
+    static REPORTED: Cell<Option<Rect>> = const {Cell::new(None)};
+}
+
+/// The one blinking text caret shared by every text field, kept in [`Context::state`](crate::Context::state).
+///
+/// A field claims it with [`Context::move_caret`] whenever it gains focus or its text or caret changes, which
+/// restarts the blink so the caret stays solid while typing. While drawing, the owner checks [`shown`] and calls
+/// [`report`] with where it draws the caret, which the host uses to place IME candidate windows and scroll views
+/// use to keep the caret in sight.
+#[derive(Debug, Clone)]
+pub struct Caret {
+    owner: Option<Uuid>,
+    elapsed: Duration,
+    rect: Option<Rect>,
+    reveal: bool,
+    /// How long the caret stays on and then off, `530ms` by default.
+    pub period: Duration,
+}
+
+impl Default for Caret {
+    fn default() -> Self {Caret{owner: None, elapsed: Duration::ZERO, rect: None, reveal: false, period: Duration::from_millis(530)}}
+}
+
+impl Caret {
+    pub fn owner(&self) -> Option<Uuid> {self.owner}
+
+    /// Whether the caret is in the on half of its blink.
+    pub fn visible(&self) -> bool {
+        self.owner.is_some() && (self.elapsed.as_millis() / self.period.as_millis().max(1)) % 2 == 0
+    }
+
+    /// Where the owner last drew the caret, in logical pixels from the top left of the screen.
+    pub fn rect(&self) -> Option<Rect> {self.rect}
+
+    pub(crate) fn claim(&mut self, owner: Uuid) {
+        self.owner = Some(owner);
+        self.elapsed = Duration::ZERO;
+        self.reveal = true;
+    }
+
+    pub(crate) fn release(&mut self, owner: Uuid) {
+        if self.owner == Some(owner) {*self = Caret{period: self.period, ..Caret::default()};}
+    }
+
+    /// Whether the blink flips within `delta` of the last frame.
+    pub(crate) fn flips_within(&self, delta: Duration) -> bool {
+        let period = self.period.as_millis().max(1);
+        self.owner.is_some() && self.elapsed.as_millis() / period != (self.elapsed + delta).as_millis() / period
+    }
+
+    pub(crate) fn advance(&mut self, delta: Duration) {
+        if self.owner.is_some() {self.elapsed += delta;}
+    }
+
+    pub(crate) fn start_draw(&self) {
+        SHOWN.set(self.visible());
+        REPORTED.set(None);
+    }
+
+    /// Takes the rect reported during the draw, returning it if it moved and whether it should be scrolled into view.
+    pub(crate) fn finish_draw(&mut self) -> (Option<Option<Rect>>, Option<Rect>) {
+        let rect = REPORTED.take().filter(|_| self.owner.is_some());
+        let moved = (rect != self.rect).then_some(rect);
+        self.rect = rect;
+        let reveal = self.rect.filter(|_| std::mem::take(&mut self.reveal));
+        (moved, reveal)
+    }
+}
+
+/// Whether the caret should be drawn this frame, for the field that owns it to read while drawing.
+pub fn shown() -> bool {SHOWN.get()}
+
+/// Records where the owner of the caret drew it this frame, as `(x, y, width, height)` on screen.
+pub fn report(rect: Rect) {REPORTED.set(Some(rect));}
+
+/// Reports the caret of the text field it is laid over while drawing, with its rect from the field's top left.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CaretMark(pub(crate) Option<Rect>);
+
+impl Drawable for CaretMark {
+    fn request_size(&self) -> RequestTree {RequestTree(SizeRequest::fixed((0.0, 0.0)), vec![])}
+
+    fn draw(&self, _sized: &SizedTree, offset: Offset, _bound: Rect) -> Vec<Instruction> {
+        if let Some((x, y, w, h)) = self.0 {report((offset.0 + x, offset.1 + y, w, h));}
+        vec![]
+    }
+}
+
+/// Sent down the tree after the caret moves, with its rect relative to the drawable receiving it, so
+/// [`ScrollView`](crate::display::ScrollView)s can scroll it into view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RevealCaret(pub Rect);
+
+impl Event for RevealCaret {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        let (x, y, w, h) = self.0;
+        children.iter().map(|Area{offset, ..}| Some(Box::new(RevealCaret((x - offset.0, y - offset.1, w, h))) as Box<dyn Event>)).collect()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::canvas::Instruction;
+use crate::drawable::{Drawable, Offset};
+use crate::event::{Event, MouseEvent};
+
+/// A drawable that replaces the system pointer, kept in [`Context::state`](crate::Context::state) and set with
+/// [`Context::set_cursor`](crate::Context::set_cursor).
+///
+/// It is drawn above everything else, focus rings included, at the position of the latest pointer event, so it
+/// never trails the frame it is drawn in the way a system cursor composited separately can.
+#[derive(Debug, Clone, Default)]
+pub struct Cursor {
+    drawable: Option<Box<dyn Drawable>>,
+    /// The point within the drawable that sits on the pointer, such as the center of a crosshair.
+    hotspot: Offset,
+    position: Option<Offset>,
+    /// Whether the host was last told to hide the system pointer.
+    hidden: bool,
+}
+
+impl Cursor {
+    pub(crate) fn set(&mut self, drawable: Option<Box<dyn Drawable>>, hotspot: Offset) {
+        self.drawable = drawable;
+        self.hotspot = hotspot;
+    }
+
+    pub fn is_custom(&self) -> bool {self.drawable.is_some()}
+
+    /// The last pointer position, in logical pixels.
+    pub fn position(&self) -> Option<Offset> {self.position}
+
+    pub(crate) fn track(&mut self, event: &dyn Event) {
+        if let Some(MouseEvent{position: Some(position), ..}) = event.downcast_ref::<MouseEvent>() {self.position = Some(*position);}
+    }
+
+    /// Returns whether the system pointer should now be shown or hidden, if that changed since the last frame.
+    pub(crate) fn visibility(&mut self) -> Option<bool> {
+        let hide = self.drawable.is_some();
+        (hide != std::mem::replace(&mut self.hidden, hide)).then_some(!hide)
+    }
+
+    pub(crate) fn draw(&self, screen: (f32, f32)) -> Vec<Instruction> {
+        let (Some(drawable), Some(position)) = (&self.drawable, self.position) else {return vec![]};
+        let request = drawable.request_size();
+        let sized = drawable.build((request.0.min_width(), request.0.min_height()), &request);
+        let offset = (position.0 - self.hotspot.0, position.1 - self.hotspot.1);
+        drawable.draw(&sized, offset, (0.0, 0.0, screen.0, screen.1))
+    }
+}
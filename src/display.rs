@@ -1,7 +1,19 @@
-use crate::drawable::{Drawable, Component};
-use crate::event::{OnEvent};
-use crate::layout::{Layout, Stack};
+use crate::drawable::{Drawable, Component, RequestTree, SizedTree, Offset, Rect, Size, Opacity, Clip, intersect};
+use crate::event::{self, OnEvent, Event, MouseEvent, MouseState, MouseButton, TickEvent, KeyboardEvent, KeyboardState, Key};
+use crate::layout::{self, Layout, Stack, Column, Area, SizeRequest, Table, Padding};
+use crate::canvas::{self, Instruction, Item, Shape, ShapeType, Color};
+use crate::emitters::{Scrollable, ScrollPhysics};
+use crate::animation::{Animated, Curve, Spring};
+use crate::accessibility::{AccessNode, Semantics, Role, Action};
+use crate::caret::RevealCaret;
+use crate::focus::FocusScope;
+use crate::portal::{Portal, Placement, Modals, Captured};
+use crate::{Context, WidthClass};
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::ops::Range;
 use std::clone::Clone;
 
 /// A container pairing a layout with a drawable element.
@@ -19,32 +31,292 @@ impl<L: Layout + Clone + 'static, D: Drawable + Clone + 'static> Bin<L, D> {
     }
 }
 
+type Builder<D> = Arc<dyn Fn() -> D>;
+
+#[derive(Clone)]
+struct Lazy<D>(Builder<D>);
+
+impl<D> Debug for Lazy<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lazy Builder...")
+    }
+}
+
+type Adapter<D> = Arc<dyn Fn(&mut Context, WidthClass) -> D>;
+
+#[derive(Clone)]
+struct Adapt<D>(Adapter<D>);
+
+impl<D> Debug for Adapt<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Responsive Builder...")
+    }
+}
+
+/// Builds its child for the screen's [`WidthClass`] and rebuilds it whenever the class changes,
+/// such as when a window is resized past a breakpoint or a tablet rotates.
+///
+///```rust
+/// Responsive::new(ctx, |ctx, class| match class {
+///     WidthClass::Compact => Box::new(Tabs::new(ctx)) as Box<dyn Drawable>,
+///     _ => Box::new(Sidebar::new(ctx)),
+/// })
+///```
+#[derive(Debug, Component, Clone)]
+pub struct Responsive<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] Adapt<D>, #[skip] WidthClass);
+
+impl<D: Drawable + Clone + 'static> Responsive<D> {
+    pub fn new(ctx: &mut Context, builder: impl Fn(&mut Context, WidthClass) -> D + 'static) -> Self {
+        let class = ctx.viewport().class;
+        Responsive(Stack::default(), builder(ctx, class), Adapt(Arc::new(builder)), class)
+    }
+
+    pub fn class(&self) -> WidthClass {self.3}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Responsive<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.is::<TickEvent>() {
+            let class = ctx.viewport().class;
+            if class != self.3 {
+                self.3 = class;
+                self.1 = (self.2.0)(ctx, class);
+            }
+        }
+        vec![event]
+    }
+}
+
+/// How an [`Opt`] animates between hidden and displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Transition {
+    /// The item appears and disappears instantly.
+    #[default]
+    None,
+    /// The item fades in and out.
+    Fade,
+    /// The item slides in from, and out towards, an offset given as a fraction of its own size.
+    Slide(f32, f32),
+    /// The item grows from and shrinks towards its center.
+    ///
+    /// Scaling works by laying the item out into a smaller area, so fixed-size drawables are revealed rather than resized.
+    Scale,
+}
+
+/// The enter transition, progress, exit transition and whether the item is entering.
+#[derive(Debug, Clone, PartialEq)]
+struct OptLayout(Transition, f32, Transition, bool);
+
+impl OptLayout {
+    fn new(display: bool) -> Self {OptLayout(Transition::None, if display {1.0} else {0.0}, Transition::None, display)}
+    fn transition(&self) -> Transition {if self.3 {self.0} else {self.2}}
+}
+
+impl Layout for OptLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        children.first().copied().unwrap_or_default()
+    }
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        children.iter().map(|child| {
+            let size = child.get(size);
+            let hidden = 1.0 - self.1;
+            match self.transition() {
+                Transition::Slide(x, y) => Area{offset: (x * size.0 * hidden, y * size.1 * hidden), size},
+                Transition::Scale => Area{
+                    offset: (size.0 * hidden / 2.0, size.1 * hidden / 2.0),
+                    size: (size.0 * self.1, size.1 * self.1)
+                },
+                _ => Area{offset: (0.0, 0.0), size}
+            }
+        }).collect()
+    }
+}
+
+/// A container pairing a layout with a dynamic list of drawables.
+#[derive(Debug, Component, Clone)]
+pub struct Group<L: Layout + Clone + 'static>(pub L, Vec<Box<dyn Drawable>>);
+
+impl<L: Layout + Clone + 'static> OnEvent for Group<L> {}
+
+impl<L: Layout + Clone + 'static> Group<L> {
+    pub fn new(layout: L, children: Vec<Box<dyn Drawable>>) -> Self {Group(layout, children)}
+
+    pub fn push(&mut self, child: impl Drawable) {self.1.push(Box::new(child));}
+    pub fn insert(&mut self, index: usize, child: impl Drawable) {self.1.insert(index, Box::new(child));}
+    pub fn remove(&mut self, index: usize) -> Box<dyn Drawable> {self.1.remove(index)}
+    pub fn clear(&mut self) {self.1.clear();}
+
+    pub fn len(&self) -> usize {self.1.len()}
+    pub fn is_empty(&self) -> bool {self.1.is_empty()}
+
+    pub fn items(&mut self) -> &mut Vec<Box<dyn Drawable>> {&mut self.1}
+    pub fn get_layout(&mut self) -> &mut L {&mut self.0}
+}
+
+/// A container that shows a placeholder in place of its [`Group`] whenever the group has no children.
+#[derive(Debug, Component, Clone)]
+pub struct EmptyState<L: Layout + Clone + 'static, P: Drawable + Clone + 'static>(Stack, EitherOr<Group<L>, P>);
+
+impl<L: Layout + Clone + 'static, P: Drawable + Clone + 'static> EmptyState<L, P> {
+    pub fn new(group: Group<L>, placeholder: P) -> Self {
+        let mut either = EitherOr::new(group, placeholder);
+        either.display_left(!either.left().is_empty());
+        EmptyState(Stack::default(), either)
+    }
+
+    /// Returns the group, the placeholder is swapped in or out on the next frame.
+    pub fn group(&mut self) -> &mut Group<L> {self.1.left()}
+    pub fn placeholder(&mut self) -> &mut P {self.1.right()}
+    pub fn is_empty(&mut self) -> bool {self.1.left().is_empty()}
+}
+
+impl<L: Layout + Clone + 'static, P: Drawable + Clone + 'static> OnEvent for EmptyState<L, P> {
+    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_some() {
+            let populated = !self.1.left().is_empty();
+            if populated != self.1.is_left() {self.1.display_left(populated);}
+        }
+        vec![event]
+    }
+}
+
 /// A container that optionally displays a drawable item, toggling between visible and hidden states.
+///
+/// With a [`Transition`] set, toggling animates along a [`Curve`] and a hidden item stays
+/// drawn until it has finished animating out.
 #[derive(Debug, Component, Clone)]
-pub struct Opt<D: Drawable + Clone + 'static>(Stack, Option<D>, #[skip] Option<D>);
-impl<D: Drawable + Clone + 'static> OnEvent for Opt<D> {}
+pub struct Opt<D: Drawable + Clone + 'static>(
+    OptLayout, Option<Opacity<D>>, #[skip] Option<D>, #[skip] Option<Lazy<D>>, #[skip] bool, #[skip] Animated<f32>
+);
 
 impl<D: Drawable + Clone + 'static> Opt<D> {
     pub fn new(item: D, display: bool) -> Self {
         match display {
-            true => Opt(Stack::default(), Some(item), None),
-            false => Opt(Stack::default(), None, Some(item)),
+            true => Opt(OptLayout::new(true), Some(Opacity(1.0, item)), None, None, true, Animated::new(1.0, Curve::default())),
+            false => Opt(OptLayout::new(false), None, Some(item), None, false, Animated::new(0.0, Curve::default())),
         }
     }
 
-    pub fn display(&mut self, display: bool) {
+    /// Creates an [`Opt`] whose item isn't built until it is first displayed or accessed, then kept.
+    pub fn lazy(builder: impl Fn() -> D + 'static, display: bool) -> Self {
+        Self::from_builder(Arc::new(builder), display)
+    }
+
+    fn from_builder(builder: Builder<D>, display: bool) -> Self {
         match display {
-            true if self.1.is_none() => self.1 = self.2.take(),
-            false if self.2.is_none() => self.2 = self.1.take(),
+            true => Opt::new(builder(), true),
+            false => Opt(OptLayout::new(false), None, None, Some(Lazy(builder)), false, Animated::new(0.0, Curve::default())),
+        }
+    }
+
+    /// Animates future toggles with `transition` along `curve`.
+    pub fn set_transition(&mut self, transition: Transition, curve: Curve) {
+        self.set_transitions(transition, transition, curve);
+    }
+
+    /// Animates showing the item with `enter` and hiding it with `exit`, both along `curve`.
+    pub fn set_transitions(&mut self, enter: Transition, exit: Transition, curve: Curve) {
+        self.0.0 = enter;
+        self.0.2 = exit;
+        self.5.set_curve(curve);
+    }
+
+    fn build(&mut self) {
+        if self.1.is_none() && self.2.is_none() {
+            self.2 = self.3.take().map(|builder| (builder.0)());
+        }
+    }
+
+    fn set_progress(&mut self, progress: f32) {
+        self.0.1 = progress;
+        if let Some(item) = &mut self.1 {
+            item.0 = if self.0.transition() == Transition::Fade {progress.clamp(0.0, 1.0)} else {1.0};
+        }
+        if progress <= 0.0 && !self.4 && self.2.is_none() {
+            self.2 = self.1.take().map(|item| item.1);
+        }
+    }
+
+    pub fn display(&mut self, display: bool) {
+        self.4 = display;
+        self.0.3 = display;
+        if display {
+            self.build();
+            if self.1.is_none() {self.1 = self.2.take().map(|item| Opacity(0.0, item));}
+        }
+        let target = if display {1.0} else {0.0};
+        match self.0.transition() {
+            Transition::None => self.5.set(target),
+            _ if *self.5.target() != target => self.5.animate_to(target),
             _ => {}
         }
+        self.set_progress(*self.5.get());
     }
 
     pub fn inner(&mut self) -> &mut D {
-        self.1.as_mut().unwrap_or_else(|| self.2.as_mut().unwrap())
+        self.build();
+        match &mut self.1 {
+            Some(item) => &mut item.1,
+            None => self.2.as_mut().unwrap()
+        }
+    }
+
+    pub fn is_showing(&self) -> bool { self.4 }
+
+    /// Returns whether the item has been built, which is always true unless created with [`Opt::lazy`].
+    pub fn is_built(&self) -> bool { self.3.is_none() }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Opt<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_some() && self.5.tick(ctx.delta()) {
+            self.set_progress(*self.5.get());
+            if self.5.is_running() {ctx.request_frame();}
+        }
+        vec![event]
     }
+}
+
+type Predicate<T> = Arc<dyn Fn(&T) -> bool>;
+
+#[derive(Clone)]
+struct Condition<T>(Predicate<T>);
 
-    pub fn is_showing(&self) -> bool { self.1.is_some() }
+impl<T> Debug for Condition<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Condition...")
+    }
+}
+
+/// A container that displays its item whenever a condition on a value in [`Context::state`] holds.
+///
+/// The value is watched, so the condition is only rechecked when it changes. The item is hidden while
+/// no value of type `T` is stored.
+#[derive(Debug, Component, Clone)]
+pub struct When<T: Clone + Debug + 'static, D: Drawable + Clone + 'static>(Stack, Opt<D>, #[skip] Condition<T>, #[skip] bool);
+
+impl<T: Clone + Debug + 'static, D: Drawable + Clone + 'static> When<T, D> {
+    pub fn new(item: D, condition: impl Fn(&T) -> bool + 'static) -> Self {
+        When(Stack::default(), Opt::new(item, false), Condition(Arc::new(condition)), false)
+    }
+
+    pub fn inner(&mut self) -> &mut Opt<D> {&mut self.1}
+}
+
+impl<T: Clone + Debug + 'static, D: Drawable + Clone + 'static> OnEvent for When<T, D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        let first = !self.3 && event.downcast_ref::<TickEvent>().is_some();
+        if first || event.downcast_ref::<event::StateChanged<T>>().is_some() {
+            if first {
+                ctx.state.watch::<T>();
+                self.3 = true;
+            }
+            let display = ctx.state.get::<T>().map(|value| (self.2.0)(value)).unwrap_or_default();
+            if display != self.1.is_showing() {self.1.display(display);}
+        }
+        vec![event]
+    }
 }
 
 /// A container that holds two drawables but displays only one at a time, allowing toggling between them.
@@ -63,42 +335,1150 @@ impl<L: Drawable + Clone + 'static, R: Drawable + Clone + 'static> EitherOr<L, R
         self.2.display(!display_left);
     }
 
+    /// Animates future swaps with `transition` along `curve`, e.g. a crossfade with [`Transition::Fade`].
+    pub fn set_transition(&mut self, transition: Transition, curve: Curve) {
+        self.1.set_transition(transition, curve);
+        self.2.set_transition(transition, curve);
+    }
+
     pub fn left(&mut self) -> &mut L { self.1.inner() }
     pub fn right(&mut self) -> &mut R { self.2.inner() }
     pub fn is_left(&self) -> bool { self.1.is_showing() }
 }
 
+/// A wrapper that plays an enter [`Transition`] when it first receives a frame and an exit transition on [`Transitioned::exit`].
+///
+/// The child stays drawn until the exit has finished, so a parent should keep it until [`Transitioned::has_exited`]
+/// before removing it.
+#[derive(Debug, Component, Clone)]
+pub struct Transitioned<D: Drawable + Clone + 'static>(Stack, Opt<D>, #[skip] bool, #[skip] bool);
+
+impl<D: Drawable + Clone + 'static> Transitioned<D> {
+    pub fn new(item: D, enter: Transition, exit: Transition, curve: Curve) -> Self {
+        let mut item = Opt::new(item, false);
+        item.set_transitions(enter, exit, curve);
+        Transitioned(Stack::default(), item, false, false)
+    }
+
+    /// Starts the exit transition.
+    pub fn exit(&mut self) {
+        self.3 = true;
+        self.1.display(false);
+    }
+
+    pub fn has_exited(&self) -> bool {self.3 && self.1.1.is_none()}
+
+    pub fn inner(&mut self) -> &mut D {self.1.inner()}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Transitioned<D> {
+    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if !self.2 && event.downcast_ref::<TickEvent>().is_some() {
+            self.2 = true;
+            if !self.3 {self.1.display(true);}
+        }
+        vec![event]
+    }
+}
+
+/// A container holding a single drawable that can be swapped for another at runtime.
+///
+/// The next frame lays out the new drawable from scratch, so the parent doesn't need to be rebuilt.
+#[derive(Debug, Component, Clone)]
+pub struct Slot(Stack, Box<dyn Drawable>);
+impl OnEvent for Slot {}
+
+impl Slot {
+    pub fn new(item: impl Drawable) -> Self {Slot(Stack::default(), Box::new(item))}
+
+    /// Replaces the held drawable, returning the previous one.
+    pub fn set(&mut self, item: impl Drawable) -> Box<dyn Drawable> {
+        std::mem::replace(&mut self.1, Box::new(item))
+    }
+
+    pub fn get(&mut self) -> &mut Box<dyn Drawable> {&mut self.1}
+
+    /// Returns the held drawable if it is of type `D`.
+    pub fn downcast<D: Drawable>(&mut self) -> Option<&mut D> {self.1.downcast_mut::<D>()}
+}
+
+/// Error returned when an [`Enum`] is asked for a variant it doesn't contain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownVariant<K>(pub K);
+
+impl<K: Debug> std::fmt::Display for UnknownVariant<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Enum has no variant {:?}", self.0)
+    }
+}
+
+impl<K: Debug> std::error::Error for UnknownVariant<K> {}
+
 /// A container that holds multiple drawables but displays only one at a time, allowing toggling between them.
+///
+/// Variants are keyed by `K`, ideally a plain enum of the app's screens, and keep the order they were given in.
+#[derive(Debug, Component, Clone)]
+pub struct Enum<K: Eq + Hash + Clone + Debug + 'static, D: Drawable + Clone + 'static>(Stack, HashMap<K, Opt<D>>, #[skip] K, #[skip] Vec<K>);
+impl<K: Eq + Hash + Clone + Debug + 'static, D: Drawable + Clone + 'static> OnEvent for Enum<K, D> {}
+
+impl<K: Eq + Hash + Clone + Debug + 'static, D: Drawable + Clone + 'static> Enum<K, D> {
+    /// Creates a new [`Enum`] component with the given drawable items, displaying the `start` variant.
+    pub fn new(items: Vec<(K, D)>, start: K) -> Result<Self, UnknownVariant<K>> {
+        if !items.iter().any(|(key, _)| *key == start) {return Err(UnknownVariant(start));}
+        let order = items.iter().map(|(key, _)| key.clone()).collect();
+        let items = items.into_iter().map(|(key, item)| {
+            let display = key == start;
+            (key, Opt::new(item, display))
+        }).collect();
+
+        Ok(Enum(Stack::default(), items, start, order))
+    }
+
+    /// Displays only the item matching the given key and hides all others.
+    /// If the key doesn't exist the current item stays displayed.
+    pub fn display(&mut self, key: &K) -> Result<(), UnknownVariant<K>> {
+        if !self.1.contains_key(key) {return Err(UnknownVariant(key.clone()));}
+        self.2 = key.clone();
+        for (k, v) in self.1.iter_mut() {
+            v.display(k == key);
+        }
+        Ok(())
+    }
+
+    /// Creates an [`Enum`] whose variants are only built when first displayed or accessed.
+    pub fn lazy(items: Vec<(K, Box<dyn Fn() -> D>)>, start: K) -> Result<Self, UnknownVariant<K>> {
+        if !items.iter().any(|(key, _)| *key == start) {return Err(UnknownVariant(start));}
+        let order = items.iter().map(|(key, _)| key.clone()).collect();
+        let items = items.into_iter().map(|(key, builder)| {
+            let display = key == start;
+            (key, Opt::from_builder(Arc::from(builder), display))
+        }).collect();
+
+        Ok(Enum(Stack::default(), items, start, order))
+    }
+
+    pub fn current(&self) -> &K { &self.2 }
+    pub fn drawable(&mut self) -> &mut Opt<D> {
+        self.1.get_mut(&self.2).expect("Enum current variant is always present")
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&mut D> { self.1.get_mut(key).map(Opt::inner) }
+
+    /// Iterates over the variant keys in the order they were given.
+    pub fn keys(&self) -> impl Iterator<Item = &K> { self.3.iter() }
+
+    /// Iterates over every variant, displayed or not, in the order they were given.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut D)> {
+        let mut items = self.1.iter_mut().collect::<HashMap<_, _>>();
+        self.3.iter().flat_map(move |key| items.remove(key).map(|item| (key, item.inner())))
+    }
+}
+
+/// A solid color that fills whatever space it is given, used to dim content behind overlays.
+#[derive(Debug, Clone, Copy)]
+pub struct Scrim(pub Color);
+
+impl Drawable for Scrim {
+    fn request_size(&self) -> RequestTree {RequestTree(SizeRequest::fill(), vec![])}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let shape = Shape{shape: ShapeType::Rectangle(0.0, sized.0, 0.0), color: self.0};
+        vec![Instruction(canvas::Area{offset, bounds: Some(bound)}, Item::Shape(shape))]
+    }
+}
+
+/// The screen edge a [`Drawer`] slides in from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrawerEdge {
+    #[default]
+    Left,
+    Right
+}
+
+/// Whether an open [`Drawer`] covers the main content or pushes it aside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrawerMode {
+    #[default]
+    Over,
+    Push
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DrawerLayout(DrawerEdge, DrawerMode, f32);
+
+impl Layout for DrawerLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {children[0]}
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        let width = children[2].get(size).0;
+        let shown = width * self.2;
+        let (panel, push) = match self.0 {
+            DrawerEdge::Left => (shown - width, shown),
+            DrawerEdge::Right => (size.0 - shown, -shown),
+        };
+        let content = if self.1 == DrawerMode::Push {push} else {0.0};
+        vec![
+            Area{offset: (content, 0.0), size: children[0].get(size)},
+            // A closed drawer's scrim takes no space, or it would be hit before the content under it.
+            Area{offset: (0.0, 0.0), size: if self.2 > 0.0 {size} else {(0.0, 0.0)}},
+            Area{offset: (panel, 0.0), size: (width, size.1)},
+        ]
+    }
+}
+
+const DRAWER_EDGE_WIDTH: f32 = 20.0;
+const DRAWER_SCRIM_ALPHA: f32 = 128.0;
+
+/// A container that slides a panel in from the left or right edge over (or pushing) the main content.
+///
+/// The drawer opens with [`Drawer::open`] or an edge swipe on mobile and closes when the scrim behind the panel is pressed.
+/// While open, Tab only moves between the [`Focusable`](crate::focus::Focusable)s in the panel.
 #[derive(Debug, Component, Clone)]
-pub struct Enum<D: Drawable + Clone + 'static>(Stack, HashMap<String, Opt<D>>, #[skip] String);
-impl<D: Drawable + Clone + 'static> OnEvent for Enum<D> {}
+pub struct Drawer<C: Drawable + Clone + 'static, P: Drawable + Clone + 'static>(
+    DrawerLayout, C, Opt<Scrim>, FocusScope<P>, #[skip] bool, #[skip] Option<((f32, f32), f32, bool)>, #[skip] Animated<f32>
+);
+
+impl<C: Drawable + Clone + 'static, P: Drawable + Clone + 'static> Drawer<C, P> {
+    pub fn new(content: C, panel: P, edge: DrawerEdge, mode: DrawerMode) -> Self {
+        let slide = Animated::new(0.0, Curve::Spring(Spring::default()));
+        let mut panel = FocusScope::new(panel);
+        panel.set_trapping(false);
+        Drawer(DrawerLayout(edge, mode, 0.0), content, Opt::new(Scrim(Color(0, 0, 0, 0)), false), panel, false, None, slide)
+    }
 
-impl<D: Drawable + Clone + 'static> Enum<D> {
-    /// Creates a new [`Enum`] component, Clone with the given drawable items.
-    /// The first item will be visible by default.
-    pub fn new(items: Vec<(String, D)>, start: String) -> Self {
-        let items = items.into_iter().map(|(name, item)| {
-            (name.to_string(), Opt::new(item, name == start))
-        }).collect::<Vec<(String, Opt<D>)>>();
+    pub fn open(&mut self) {self.4 = true;}
+    pub fn close(&mut self) {self.4 = false;}
+    pub fn toggle(&mut self) {self.4 = !self.4;}
+    pub fn is_open(&self) -> bool {self.4}
 
-        Enum(Stack::default(), items.into_iter().collect(), start.to_string())
+    pub fn content(&mut self) -> &mut C {&mut self.1}
+    pub fn panel(&mut self) -> &mut P {self.3.inner()}
+
+    fn set_progress(&mut self, progress: f32) {
+        self.0.2 = progress.clamp(0.0, 1.0);
+        self.2.display(self.0.2 > 0.0);
+        self.2.inner().0.3 = (DRAWER_SCRIM_ALPHA * self.0.2) as u8;
     }
 
-    /// Displays only the item matching the given name and hides all others. 
-    /// If the key doesn't exist, defaults to the first item.
-    pub fn display(&mut self, name: &str) -> bool {
-        if self.1.contains_key(name) {  
-            self.2 = name.to_string();
+    fn settle(&mut self, ctx: &mut Context, open: bool) {
+        if self.4 != open {ctx.emit(event::Drawer::Opened(open));}
+        self.4 = open;
+    }
+}
 
-            for (k, v) in self.1.iter_mut() {
-                v.display(*k == name);
+impl<C: Drawable + Clone + 'static, P: Drawable + Clone + 'static> OnEvent for Drawer<C, P> {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_some() && self.5.is_none() {
+            let target = if self.4 {1.0} else {0.0};
+            if *self.6.target() != target {self.6.animate_to(target);}
+            if self.6.tick(ctx.delta()) {self.set_progress(*self.6.get());}
+            if self.6.is_running() {ctx.request_frame();}
+            self.3.set_trapping(self.4);
+        } else if let Some(MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() {
+            let width = sized.1.get(2).map(|(_, panel)| panel.0.0).unwrap_or_default().max(1.0);
+            let edge = match self.0.0 {
+                DrawerEdge::Left => position.map(|p| p.0 < DRAWER_EDGE_WIDTH),
+                DrawerEdge::Right => position.map(|p| p.0 > sized.0.0 - DRAWER_EDGE_WIDTH),
+            }.unwrap_or_default();
+
+            match (state, position) {
+                (MouseState::Pressed(MouseButton::Left), Some(position)) if self.4 => {
+                    let on_scrim = sized.1.get(2).map(|(offset, panel)| {
+                        position.0 < offset.0 || position.0 > offset.0 + panel.0.0
+                    }).unwrap_or_default();
+                    self.5 = Some((*position, self.0.2, on_scrim));
+                    if on_scrim {return vec![];}
+                },
+                (MouseState::Pressed(MouseButton::Left), Some(position)) if crate::IS_MOBILE && edge => {
+                    self.5 = Some((*position, self.0.2, false));
+                    return vec![];
+                },
+                (MouseState::Moved, Some(position)) if self.5.is_some() => {
+                    let (start, progress, _) = self.5.unwrap();
+                    let delta = match self.0.0 {
+                        DrawerEdge::Left => position.0 - start.0,
+                        DrawerEdge::Right => start.0 - position.0,
+                    };
+                    self.set_progress(progress + delta / width);
+                    self.6.set(self.0.2);
+                },
+                (MouseState::Released(MouseButton::Left), _) if self.5.is_some() => {
+                    let (start, _, on_scrim) = self.5.take().unwrap();
+                    let moved = position.map(|p| (p.0 - start.0).abs() > 5.0).unwrap_or(true);
+                    match moved {
+                        true => self.settle(ctx, self.0.2 > 0.5),
+                        false if on_scrim => self.settle(ctx, false),
+                        false => {}
+                    }
+                    if on_scrim {return vec![];}
+                },
+                _ => {}
             }
-            true
-        } else {false}
+        }
+        vec![event]
+    }
+}
+
+/// A rounded scrollbar thumb drawn along the right edge of a [`ScrollView`].
+#[derive(Debug, Clone, Copy)]
+pub struct Scrollbar(pub Color, pub f32);
+
+impl Drawable for Scrollbar {
+    fn request_size(&self) -> RequestTree {
+        RequestTree(SizeRequest::new(self.1, 0.0, self.1, f32::MAX), vec![])
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        if sized.0.1 <= 0.0 {return vec![];}
+        let shape = Shape{shape: ShapeType::RoundedRectangle(0.0, sized.0, 0.0, self.1 / 2.0), color: self.0};
+        vec![Instruction(canvas::Area{offset, bounds: Some(bound)}, Item::Shape(shape))]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ScrollState {
+    offset: f32,
+    content: f32,
+    viewport: f32,
+    /// The top and height of each direct child of the content, from the most recent layout.
+    children: Vec<(f32, f32)>,
+}
+
+impl ScrollState {
+    fn max(&self) -> f32 {(self.content - self.viewport).max(0.0)}
+}
+
+#[derive(Debug, Clone)]
+struct ScrollLayout(Arc<Mutex<ScrollState>>);
+
+impl Layout for ScrollLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let content = children[0];
+        SizeRequest::new(content.min_width(), 0.0, content.max_width(), content.max_height())
+    }
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        let content = children[0].get(size);
+        let mut state = self.0.lock().unwrap();
+        state.content = content.1;
+        state.viewport = size.1;
+        state.offset = state.offset.clamp(0.0, state.max());
+
+        let mut areas = vec![Area{offset: (0.0, -state.offset), size: content}];
+        if let Some(bar) = children.get(1) {
+            let width = bar.get(size).0;
+            let (height, y) = match state.max() > 0.0 {
+                true => {
+                    let height = (size.1 * size.1 / content.1).max(24.0).min(size.1);
+                    (height, (size.1 - height) * state.offset / state.max())
+                },
+                false => (0.0, 0.0)
+            };
+            areas.push(Area{offset: (size.0 - width, y), size: (width, height)});
+        }
+        areas
+    }
+}
+
+/// Where [`ScrollView::scroll_to`] and [`Column::scroll_to`](crate::layout::Column::scroll_to) place a child within the view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Scrolls as little as possible to bring the child fully into view.
+    Nearest,
+}
+
+impl ScrollAlign {
+    pub(crate) fn offset(&self, (top, height): (f32, f32), current: f32, viewport: f32) -> f32 {
+        match self {
+            ScrollAlign::Start => top,
+            ScrollAlign::Center => top + (height - viewport) / 2.0,
+            ScrollAlign::End => top + height - viewport,
+            ScrollAlign::Nearest if top < current => top,
+            ScrollAlign::Nearest if top + height > current + viewport => top + height - viewport,
+            ScrollAlign::Nearest => current,
+        }
+    }
+}
+
+/// A scrolling container that composes a [`Scrollable`] around its content with an optional scrollbar.
+///
+/// Unlike a scrolling [`Column`](crate::layout::Column), the scroll offset can be read and driven from app code,
+/// jumping or animating to an offset or a child. Each such scroll emits [`event::ScrollFinished`] with the view's
+/// [`ScrollView::id`] once it settles.
+#[derive(Debug, Component, Clone)]
+pub struct ScrollView<D: Drawable + Clone + 'static>(
+    ScrollLayout, Scrollable<D>, Option<Scrollbar>, #[skip] Option<(usize, ScrollAlign, bool)>, #[skip] Animated<f32>, #[skip] uuid::Uuid
+);
+
+impl<D: Drawable + Clone + 'static> ScrollView<D> {
+    pub fn new(content: D, scrollbar: Option<Color>) -> Self {
+        ScrollView(
+            ScrollLayout(Arc::new(Mutex::new(ScrollState::default()))),
+            Scrollable::new(content), scrollbar.map(|c| Scrollbar(c, 6.0)), None,
+            Animated::new(0.0, Curve::default()), uuid::Uuid::new_v4()
+        )
+    }
+
+    /// The id carried by the [`event::ScrollFinished`] this view emits.
+    pub fn id(&self) -> uuid::Uuid {self.5}
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1.inner}
+
+    /// Replaces how the view coasts after a fling, see [`ScrollPhysics`].
+    pub fn set_physics(&mut self, physics: impl ScrollPhysics + 'static) {self.1.set_physics(physics)}
+
+    /// Returns the current scroll offset from the top of the content.
+    pub fn offset(&self) -> f32 {self.0.0.lock().unwrap().offset}
+
+    fn place(&self, offset: f32) {
+        let mut state = self.0.0.lock().unwrap();
+        state.offset = offset.clamp(0.0, state.max());
+    }
+
+    /// Sets the scroll offset, clamped to the scrollable range, stopping any animated scroll.
+    pub fn set_offset(&mut self, offset: f32) {
+        self.4.cancel();
+        self.place(offset);
+    }
+
+    pub fn scroll_by(&mut self, delta: f32) {
+        let offset = self.offset() + delta;
+        self.set_offset(offset);
+    }
+
+    /// Animates the scroll offset by `delta`, adding to the end of a scroll already animating.
+    pub fn animate_by(&mut self, delta: f32) {
+        let from = match self.4.is_running() {
+            true => *self.4.target(),
+            false => {self.4.set(self.offset()); self.offset()}
+        };
+        let state = self.0.0.lock().unwrap();
+        self.4.animate_to((from + delta).clamp(0.0, state.max()));
     }
 
-    pub fn current(&self) -> String { self.2.to_string() }
-    pub fn drawable(&mut self) -> &mut Opt<D> { 
-        self.1.get_mut(&self.2).unwrap() 
+    /// Sets the curve animated scrolls follow.
+    pub fn set_curve(&mut self, curve: Curve) {self.4.set_curve(curve)}
+
+    /// Returns how far the view can still scroll before reaching the end of the content.
+    pub fn remaining(&self) -> f32 {
+        let state = self.0.0.lock().unwrap();
+        state.max() - state.offset
+    }
+
+    /// Returns the height of the visible area from the most recent layout.
+    pub fn viewport(&self) -> f32 {self.0.0.lock().unwrap().viewport}
+
+    /// Scrolls so the direct child of the content at `index` sits at the top of the view.
+    pub fn scroll_to_child(&mut self, index: usize) {self.scroll_to(index, ScrollAlign::Start)}
+
+    /// Jumps to the direct child of the content at `index`, placed in the view as `align` asks.
+    ///
+    /// Child positions are taken from the most recent layout, so the scroll is applied on the next event.
+    pub fn scroll_to(&mut self, index: usize, align: ScrollAlign) {self.3 = Some((index, align, false));}
+
+    /// Animates to the direct child of the content at `index`, see [`ScrollView::scroll_to`].
+    pub fn animate_to(&mut self, index: usize, align: ScrollAlign) {self.3 = Some((index, align, true));}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for ScrollView<D> {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        let content = sized.1.first().and_then(|(_, s)| s.1.first()).and_then(|(_, m)| m.1.first()).map(|(_, c)| c);
+        if let Some(content) = content {
+            self.0.0.lock().unwrap().children = content.1.iter().map(|(offset, child)| (offset.1, child.0.1)).collect();
+        }
+
+        if let Some((index, align, animate)) = self.3.take() {
+            let target = {
+                let state = self.0.0.lock().unwrap();
+                state.children.get(index).map(|child| align.offset(*child, state.offset, state.viewport))
+            };
+            match (target, animate) {
+                (Some(target), true) => {
+                    let delta = target - self.offset();
+                    self.4.cancel();
+                    self.animate_by(delta);
+                    ctx.request_frame();
+                },
+                (Some(target), false) => {
+                    self.set_offset(target);
+                    ctx.emit(event::ScrollFinished(self.5));
+                },
+                (None, _) => {}
+            }
+        }
+
+        if event.is::<TickEvent>() && self.4.tick(ctx.delta()) {
+            self.place(*self.4.get());
+            match self.4.is_running() {
+                true => ctx.request_frame(),
+                false => ctx.emit(event::ScrollFinished(self.5)),
+            }
+        }
+
+        if let Some(MouseEvent{position: Some(position), state: MouseState::Scroll(_, y)}) = event.downcast_ref::<MouseEvent>() {
+            self.scroll_by(*y);
+            return vec![Box::new(MouseEvent{position: Some(*position), state: MouseState::Moved})];
+        }
+        if let Some(RevealCaret((_, y, _, h))) = event.downcast_ref::<RevealCaret>() {
+            let viewport = self.viewport();
+            if *y < 0.0 {self.scroll_by(*y)} else if y + h > viewport {self.scroll_by(y + h - viewport)}
+        }
+        vec![event]
+    }
+}
+
+/// Which directions a [`ScrollRegion`] scrolls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollAxes {
+    #[default]
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl ScrollAxes {
+    fn horizontal(&self) -> bool {*self != ScrollAxes::Vertical}
+    fn vertical(&self) -> bool {*self != ScrollAxes::Horizontal}
+}
+
+#[derive(Debug, Clone, Default)]
+struct RegionState {
+    offset: (f32, f32),
+    max: (f32, f32),
+}
+
+#[derive(Debug, Clone)]
+struct RegionLayout(ScrollAxes, Arc<Mutex<RegionState>>);
+
+impl Layout for RegionLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let content = children[0];
+        SizeRequest::new(
+            if self.0.horizontal() {0.0} else {content.min_width()},
+            if self.0.vertical() {0.0} else {content.min_height()},
+            content.max_width(), content.max_height()
+        )
+    }
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        let content = children[0].get(size);
+        let mut state = self.1.lock().unwrap();
+        state.max = ((content.0 - size.0).max(0.0), (content.1 - size.1).max(0.0));
+        state.offset = (state.offset.0.clamp(0.0, state.max.0), state.offset.1.clamp(0.0, state.max.1));
+        vec![Area{offset: (-state.offset.0, -state.offset.1), size: content}]
+    }
+}
+
+/// A container that lets its content be wider or taller than the space it is given and scrolls it with the
+/// wheel or trackpad, for side-scrolling carousels and wide tables.
+///
+/// Horizontal scroll deltas move the content sideways. A region that only scrolls horizontally also turns a plain
+/// wheel's vertical deltas sideways while it can still move that way, so mice without a horizontal wheel can
+/// still reach everything, and passes them on to its content untouched once it can't.
+#[derive(Debug, Component, Clone)]
+pub struct ScrollRegion<D: Drawable + Clone + 'static>(RegionLayout, D);
+
+impl<D: Drawable + Clone + 'static> ScrollRegion<D> {
+    pub fn new(content: D, axes: ScrollAxes) -> Self {
+        ScrollRegion(RegionLayout(axes, Arc::new(Mutex::new(RegionState::default()))), content)
+    }
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+
+    /// Returns the current scroll offset from the top left of the content.
+    pub fn offset(&self) -> (f32, f32) {self.0.1.lock().unwrap().offset}
+
+    /// Sets the scroll offset, clamped to the scrollable range of the most recent layout.
+    pub fn set_offset(&mut self, offset: (f32, f32)) {
+        let mut state = self.0.1.lock().unwrap();
+        state.offset = (offset.0.clamp(0.0, state.max.0), offset.1.clamp(0.0, state.max.1));
+    }
+
+    pub fn scroll_by(&mut self, delta: (f32, f32)) {
+        let offset = self.offset();
+        self.set_offset((offset.0 + delta.0, offset.1 + delta.1));
+    }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for ScrollRegion<D> {
+    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{position: Some(position), state: MouseState::Scroll(x, y)}) = event.downcast_ref::<MouseEvent>() {
+            let delta = match self.0.0 {
+                ScrollAxes::Horizontal if *x == 0.0 => {
+                    let state = self.0.1.lock().unwrap();
+                    let movable = (*y > 0.0 && state.offset.0 < state.max.0) || (*y < 0.0 && state.offset.0 > 0.0);
+                    if !movable {return vec![event];}
+                    (*y, 0.0)
+                },
+                ScrollAxes::Horizontal => (*x, 0.0),
+                ScrollAxes::Vertical => (0.0, *y),
+                ScrollAxes::Both => (*x, *y),
+            };
+            self.scroll_by(delta);
+            return vec![Box::new(MouseEvent{position: Some(*position), state: MouseState::Moved})];
+        }
+        vec![event]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct GridState {
+    width: f32,
+    viewport: f32,
+    offset: f32,
+    range: Range<usize>,
+}
+
+/// A scrolling grid of uniformly sized items whose column count adapts to the available width.
+///
+/// Columns are as many as fit at `min_item_width`, with the leftover width shared between them.
+/// When virtualized, only the rows intersecting the viewport are measured, built, drawn and sent events.
+#[derive(Debug, Clone)]
+pub struct GridView {
+    items: Vec<Box<dyn Drawable>>,
+    min_item_width: f32,
+    item_height: f32,
+    spacing: f32,
+    virtualize: bool,
+    state: Arc<Mutex<GridState>>,
+}
+
+impl GridView {
+    pub fn new(items: Vec<Box<dyn Drawable>>, min_item_width: f32, item_height: f32, spacing: f32, virtualize: bool) -> Self {
+        GridView{items, min_item_width, item_height, spacing, virtualize, state: Arc::new(Mutex::new(GridState::default()))}
+    }
+
+    pub fn items(&mut self) -> &mut Vec<Box<dyn Drawable>> {&mut self.items}
+    pub fn push(&mut self, item: impl Drawable) {self.items.push(Box::new(item));}
+
+    pub fn offset(&self) -> f32 {self.state.lock().unwrap().offset}
+    pub fn set_offset(&mut self, offset: f32) {self.state.lock().unwrap().offset = offset.max(0.0);}
+
+    /// Returns the number of columns and the width of each item for a given grid width.
+    pub fn columns(&self, width: f32) -> (usize, f32) {
+        let columns = (((width + self.spacing) / (self.min_item_width + self.spacing)).floor() as usize).max(1);
+        let item_width = ((width - self.spacing * (columns - 1) as f32) / columns as f32).max(0.0);
+        (columns, item_width)
+    }
+
+    fn content_height(&self, columns: usize) -> f32 {
+        let rows = self.items.len().div_ceil(columns);
+        (rows as f32 * (self.item_height + self.spacing) - self.spacing).max(0.0)
+    }
+
+    fn visible(&self, state: &GridState) -> Range<usize> {
+        if !self.virtualize || state.width <= 0.0 {return 0..self.items.len();}
+        let (columns, _) = self.columns(state.width);
+        let row = self.item_height + self.spacing;
+        let first = (state.offset / row).floor() as usize;
+        let last = ((state.offset + state.viewport) / row).ceil() as usize;
+        (first * columns).min(self.items.len())..((last + 1) * columns).min(self.items.len())
+    }
+}
+
+impl Drawable for GridView {
+    fn request_size(&self) -> RequestTree {
+        let mut state = self.state.lock().unwrap();
+        state.range = self.visible(&state);
+        let requests = RequestTree::branches(self.items[state.range.clone()].iter().map(|i| i.request_size()));
+        RequestTree(SizeRequest::new(self.min_item_width, 0.0, f32::MAX, f32::MAX), requests)
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
+        let size = request.0.get(size);
+        let mut state = self.state.lock().unwrap();
+        let (columns, width) = self.columns(size.0);
+        state.width = size.0;
+        state.viewport = size.1;
+        state.offset = state.offset.clamp(0.0, (self.content_height(columns) - size.1).max(0.0));
+
+        let start = state.range.start;
+        SizedTree::new(size, SizedTree::branches(self.items[state.range.clone()].iter().zip(request.1.iter()).enumerate().map(|(i, (item, branch))| {
+            let (row, column) = ((start + i) / columns, (start + i) % columns);
+            let offset = (
+                column as f32 * (width + self.spacing),
+                row as f32 * (self.item_height + self.spacing) - state.offset
+            );
+            (offset, item.build((width, self.item_height), branch))
+        })))
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let bound = intersect(bound, offset, sized.0);
+        if bound.2 == 0.0 || bound.3 == 0.0 {return vec![];}
+
+        let range = self.state.lock().unwrap().range.clone();
+        sized.1.iter().zip(self.items.get(range).unwrap_or_default()).flat_map(|((o, branch), item)| {
+            item.draw(branch, (offset.0 + o.0, offset.1 + o.1), bound)
+        }).collect()
+    }
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        if let Some(MouseEvent{position: Some(_), state: MouseState::Scroll(_, y)}) = event.downcast_ref::<MouseEvent>() {
+            self.state.lock().unwrap().offset += *y;
+            return;
+        }
+
+        let children = sized.1.iter().map(|(o, branch)| Area{offset: *o, size: branch.0}).collect::<Vec<_>>();
+        let range = self.state.lock().unwrap().range.clone();
+        let items = self.items.get_mut(range).unwrap_or_default();
+        event.pass(ctx, &children).into_iter().zip(items).zip(sized.1.iter()).for_each(
+            |((e, item), branch)| if let Some(e) = e {item.event(ctx, &branch.1, e);}
+        );
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        let range = self.state.lock().unwrap().range.clone();
+        let items = sized.1.iter().zip(self.items.get(range).unwrap_or_default()).flat_map(|((o, branch), item)| {
+            item.accessibility(branch, (offset.0 + o.0, offset.1 + o.1))
+        }).collect();
+        vec![AccessNode{
+            semantics: Semantics{role: Role::List, actions: vec![Action::ScrollForward, Action::ScrollBackward], ..Semantics::default()},
+            bounds: (offset.0, offset.1, sized.0.0, sized.0.1),
+            children: items
+        }]
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {
+        let range = self.state.lock().unwrap().range.clone();
+        self.items.get(range).unwrap_or_default().iter().enumerate().map(|(i, item)| (Some(i), item as &dyn Drawable)).collect()
+    }
+}
+
+/// How far above and below the view a [`ListView`] keeps items laid out, so they are ready as they scroll in.
+const LIST_OVERSCAN: f32 = 200.0;
+
+#[derive(Debug, Clone, Default)]
+struct ListState {
+    viewport: f32,
+    offset: f32,
+    /// The height of each item when last measured, `None` for items never near the view.
+    heights: Vec<Option<f32>>,
+    range: Range<usize>,
+}
+
+/// A scrolling column of items of any height that only measures, builds, draws and sends events to the items in or
+/// near the view, for chats and feeds with thousands of entries.
+///
+/// Items that have never been near the view are assumed to be `estimate` tall, so the scroll range settles as the
+/// list is scrolled through.
+#[derive(Debug, Clone)]
+pub struct ListView {
+    items: Vec<Box<dyn Drawable>>,
+    spacing: f32,
+    estimate: f32,
+    state: Arc<Mutex<ListState>>,
+}
+
+impl ListView {
+    pub fn new(items: Vec<Box<dyn Drawable>>, spacing: f32, estimate: f32) -> Self {
+        ListView{items, spacing, estimate, state: Arc::new(Mutex::new(ListState::default()))}
+    }
+
+    pub fn items(&mut self) -> &mut Vec<Box<dyn Drawable>> {&mut self.items}
+    pub fn push(&mut self, item: impl Drawable) {self.items.push(Box::new(item));}
+
+    pub fn offset(&self) -> f32 {self.state.lock().unwrap().offset}
+    pub fn set_offset(&mut self, offset: f32) {self.state.lock().unwrap().offset = offset.max(0.0);}
+
+    /// Scrolls so the item at `index` sits at the top of the view, as far as the items measured so far tell.
+    pub fn scroll_to(&mut self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.offset = self.top(&state, index);
+    }
+
+    fn height(&self, state: &ListState, index: usize) -> f32 {state.heights.get(index).copied().flatten().unwrap_or(self.estimate)}
+
+    fn top(&self, state: &ListState, index: usize) -> f32 {
+        (0..index.min(self.items.len())).map(|i| self.height(state, i) + self.spacing).sum()
+    }
+
+    fn content_height(&self, state: &ListState) -> f32 {(self.top(state, self.items.len()) - self.spacing).max(0.0)}
+
+    fn visible(&self, state: &ListState) -> Range<usize> {
+        let (start, end) = (state.offset - LIST_OVERSCAN, state.offset + state.viewport + LIST_OVERSCAN);
+        let (mut top, mut first) = (0.0, None);
+        for index in 0..self.items.len() {
+            if top > end {return first.unwrap_or(index)..index;}
+            let bottom = top + self.height(state, index);
+            if first.is_none() && bottom >= start {first = Some(index);}
+            top = bottom + self.spacing;
+        }
+        first.unwrap_or(self.items.len())..self.items.len()
+    }
+}
+
+impl Drawable for ListView {
+    fn request_size(&self) -> RequestTree {
+        let mut state = self.state.lock().unwrap();
+        state.heights.resize(self.items.len(), None);
+        state.range = self.visible(&state);
+        let requests = RequestTree::branches(self.items[state.range.clone()].iter().map(|i| i.request_size()));
+        for (index, request) in state.range.clone().zip(requests.iter()) {state.heights[index] = Some(request.0.min_height());}
+        let width = requests.iter().fold(0.0_f32, |w, request| w.max(request.0.min_width()));
+        RequestTree(SizeRequest::new(width, 0.0, f32::MAX, f32::MAX), requests)
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
+        let size = request.0.get(size);
+        let mut state = self.state.lock().unwrap();
+        state.viewport = size.1;
+        state.offset = state.offset.clamp(0.0, (self.content_height(&state) - size.1).max(0.0));
+
+        let mut y = self.top(&state, state.range.start) - state.offset;
+        SizedTree::new(size, SizedTree::branches(self.items[state.range.clone()].iter().zip(request.1.iter()).map(|(item, branch)| {
+            let height = branch.0.min_height();
+            let offset = (0.0, y);
+            y += height + self.spacing;
+            (offset, item.build(branch.0.get((size.0, height)), branch))
+        })))
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let bound = intersect(bound, offset, sized.0);
+        if bound.2 == 0.0 || bound.3 == 0.0 {return vec![];}
+
+        let range = self.state.lock().unwrap().range.clone();
+        sized.1.iter().zip(self.items.get(range).unwrap_or_default()).flat_map(|((o, branch), item)| {
+            item.draw(branch, (offset.0 + o.0, offset.1 + o.1), bound)
+        }).collect()
+    }
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        if let Some(MouseEvent{position: Some(_), state: MouseState::Scroll(_, y)}) = event.downcast_ref::<MouseEvent>() {
+            self.state.lock().unwrap().offset += *y;
+            return;
+        }
+
+        let children = sized.1.iter().map(|(o, branch)| Area{offset: *o, size: branch.0}).collect::<Vec<_>>();
+        let range = self.state.lock().unwrap().range.clone();
+        let items = self.items.get_mut(range).unwrap_or_default();
+        event.pass(ctx, &children).into_iter().zip(items).zip(sized.1.iter()).for_each(
+            |((e, item), branch)| if let Some(e) = e {item.event(ctx, &branch.1, e);}
+        );
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        let range = self.state.lock().unwrap().range.clone();
+        let items = sized.1.iter().zip(self.items.get(range).unwrap_or_default()).flat_map(|((o, branch), item)| {
+            item.accessibility(branch, (offset.0 + o.0, offset.1 + o.1))
+        }).collect();
+        vec![AccessNode{
+            semantics: Semantics{role: Role::List, actions: vec![Action::ScrollForward, Action::ScrollBackward], ..Semantics::default()},
+            bounds: (offset.0, offset.1, sized.0.0, sized.0.1),
+            children: items
+        }]
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {
+        let range = self.state.lock().unwrap().range.clone();
+        self.items.get(range).unwrap_or_default().iter().enumerate().map(|(i, item)| (Some(i), item as &dyn Drawable)).collect()
+    }
+}
+
+const TABLE_RESIZE_HANDLE: f32 = 4.0;
+const TABLE_MIN_COLUMN: f32 = 24.0;
+
+/// A table with a header row, sortable and resizable columns and row selection.
+///
+/// Pressing a header cell emits [`event::TableView::Sort`] with the new direction, dragging the right edge of a
+/// header cell resizes that column and pressing a row emits [`event::TableView::Selected`].
+/// Sorting is left to the app, which reorders its data and calls [`TableView::set_rows`].
+#[derive(Debug, Component, Clone)]
+pub struct TableView(Table, Vec<Box<dyn Drawable>>, #[skip] Option<(usize, bool)>, #[skip] Option<usize>, #[skip] Option<(usize, f32, f32)>);
+
+impl TableView {
+    pub fn new(header: Vec<Box<dyn Drawable>>, widths: Vec<f32>) -> Self {
+        TableView(Table::new(widths, 0.0, 0.0), header, None, None, None)
+    }
+
+    fn columns(&self) -> usize {self.0.0.len().max(1)}
+
+    /// Replaces every row below the header, clearing the selection.
+    pub fn set_rows(&mut self, rows: Vec<Vec<Box<dyn Drawable>>>) {
+        let columns = self.columns();
+        self.1.truncate(columns);
+        self.1.extend(rows.into_iter().flatten());
+        self.3 = None;
+    }
+
+    pub fn push_row(&mut self, row: Vec<Box<dyn Drawable>>) {self.1.extend(row);}
+    pub fn rows(&self) -> usize {(self.1.len() / self.columns()).saturating_sub(1)}
+
+    pub fn widths(&self) -> &[f32] {&self.0.0}
+    pub fn set_width(&mut self, column: usize, width: f32) {
+        if let Some(w) = self.0.widths().get_mut(column) {*w = width.max(TABLE_MIN_COLUMN);}
+    }
+
+    /// Returns the column currently sorted by and whether the sort is ascending.
+    pub fn sorted(&self) -> Option<(usize, bool)> {self.2}
+    pub fn selected(&self) -> Option<usize> {self.3}
+}
+
+impl OnEvent for TableView {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() {
+            match (state, position) {
+                (MouseState::Pressed(MouseButton::Left), Some(position)) => {
+                    let columns = self.columns();
+                    let hit = sized.1.iter().position(|(o, branch)| {
+                        position.0 >= o.0 && position.0 <= o.0 + branch.0.0 + TABLE_RESIZE_HANDLE &&
+                        position.1 >= o.1 && position.1 <= o.1 + branch.0.1
+                    });
+
+                    if let Some(index) = hit {
+                        let (row, column) = (index / columns, index % columns);
+                        let (offset, cell) = &sized.1[index];
+                        if row == 0 && position.0 > offset.0 + cell.0.0 - TABLE_RESIZE_HANDLE {
+                            // A table without widths still lays out one column, which has no width to drag.
+                            if let Some(width) = self.0.0.get(column) {
+                                self.4 = Some((column, position.0, *width));
+                                return vec![];
+                            }
+                        } else if row == 0 {
+                            let ascending = !matches!(self.2, Some((c, true)) if c == column);
+                            self.2 = Some((column, ascending));
+                            ctx.emit(event::TableView::Sort(column, ascending));
+                        } else {
+                            self.3 = Some(row - 1);
+                            ctx.emit(event::TableView::Selected(row - 1));
+                        }
+                    }
+                },
+                (MouseState::Moved, Some(position)) if self.4.is_some() => {
+                    let (column, start, width) = self.4.unwrap();
+                    self.set_width(column, width + position.0 - start);
+                    return vec![];
+                },
+                (MouseState::Released(MouseButton::Left), _) if self.4.is_some() => {
+                    let (column, ..) = self.4.take().unwrap();
+                    if let Some(width) = self.0.0.get(column) {ctx.emit(event::TableView::Resized(column, *width));}
+                    return vec![];
+                },
+                _ => {}
+            }
+        }
+        vec![event]
+    }
+}
+
+#[derive(Debug, Component, Clone)]
+struct PageContent<F: Drawable + Clone + 'static>(Column, Vec<Box<dyn Drawable>>, Opt<F>);
+impl<F: Drawable + Clone + 'static> OnEvent for PageContent<F> {}
+
+/// A scrolling list that loads its items a page at a time.
+///
+/// When the user scrolls within one screen of the end, [`event::PaginatedList::LoadMore`] is emitted with the
+/// next page number and the loading footer is shown until that page is passed to [`PaginatedList::append`].
+#[derive(Debug, Component, Clone)]
+pub struct PaginatedList<F: Drawable + Clone + 'static>(Stack, ScrollView<PageContent<F>>, #[skip] usize, #[skip] bool, #[skip] bool);
+
+impl<F: Drawable + Clone + 'static> PaginatedList<F> {
+    pub fn new(items: Vec<Box<dyn Drawable>>, footer: F, spacing: f32) -> Self {
+        let content = PageContent(Column::start(spacing), items, Opt::new(footer, false));
+        PaginatedList(Stack::default(), ScrollView::new(content, None), 0, false, true)
+    }
+
+    /// Appends the next page of items and hides the loading footer.
+    pub fn append(&mut self, items: Vec<Box<dyn Drawable>>) {
+        self.2 += 1;
+        self.3 = false;
+        let content = self.1.inner();
+        content.1.extend(items);
+        content.2.display(false);
+    }
+
+    /// Stops requesting pages, for when the source has no more items.
+    pub fn finish(&mut self) {
+        self.3 = false;
+        self.4 = false;
+        self.1.inner().2.display(false);
+    }
+
+    pub fn items(&mut self) -> &mut Vec<Box<dyn Drawable>> {&mut self.1.inner().1}
+    pub fn is_loading(&self) -> bool {self.3}
+    pub fn offset(&self) -> f32 {self.1.offset()}
+    pub fn set_offset(&mut self, offset: f32) {self.1.set_offset(offset)}
+}
+
+impl<F: Drawable + Clone + 'static> OnEvent for PaginatedList<F> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        // Until the list has been laid out there is no viewport to measure how near the end it is.
+        let measured = self.1.viewport() > 0.0;
+        if event.downcast_ref::<TickEvent>().is_some() && self.4 && !self.3 && measured && self.1.remaining() <= self.1.viewport() {
+            self.3 = true;
+            self.1.inner().2.display(true);
+            ctx.emit(event::PaginatedList::LoadMore(self.2 + 1));
+        }
+        vec![event]
+    }
+}
+
+/// A full-size wrapper that asks to be dismissed when pressed anywhere outside its child.
+///
+/// Presses outside the child emit [`event::Overlay::DismissRequested`] with this overlay's id and are
+/// consumed, and since the overlay fills its space nothing drawn beneath it receives them either.
+#[derive(Debug, Component, Clone)]
+pub struct Overlay<D: Drawable + Clone + 'static>(Stack, D, #[skip] uuid::Uuid);
+
+impl<D: Drawable + Clone + 'static> Overlay<D> {
+    pub fn new(child: D, x: layout::Offset, y: layout::Offset) -> Self {
+        Overlay(Stack(x, y, layout::Size::Fill, layout::Size::Fill, Padding::default()), child, uuid::Uuid::new_v4())
+    }
+
+    pub fn id(&self) -> uuid::Uuid {self.2}
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Overlay<D> {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{position, state: MouseState::Pressed(_)}) = event.downcast_ref::<MouseEvent>() {
+            let inside = position.zip(sized.1.first()).is_some_and(|(p, (offset, child))| {
+                p.0 > offset.0 && p.0 < offset.0 + child.0.0 && p.1 > offset.1 && p.1 < offset.1 + child.0.1
+            });
+            if !inside {
+                ctx.emit(event::Overlay::DismissRequested(self.2));
+                return vec![];
+            }
+        }
+        vec![event]
+    }
+}
+
+/// The backdrop and dialog a [`Modal`] draws above the app while it is open.
+#[derive(Debug, Component, Clone)]
+struct ModalLayer<D: Drawable + Clone + 'static>(Stack, Scrim, FocusScope<D>, #[skip] uuid::Uuid);
+
+impl<D: Drawable + Clone + 'static> OnEvent for ModalLayer<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        match event.downcast::<Captured>() {
+            // The layer covers the screen from its top left corner, so positions from the root already fit it.
+            Ok(captured) if ctx.state.get_or_default::<Modals>().top() == Some(self.3) => match *captured {
+                Captured::Mouse(mouse) => vec![Box::new(mouse)],
+                Captured::Keyboard(key) => vec![Box::new(key)],
+                Captured::Touch(touch) => vec![Box::new(touch)],
+            },
+            Ok(_) => vec![],
+            Err(event) => vec![event],
+        }
+    }
+}
+
+/// A dialog drawn centered above the whole app on a dimmed backdrop while open.
+///
+/// While it is open, pointer and key events reach only the dialog, Tab stays inside it and Escape or a press on
+/// the backdrop closes it, emitting [`event::Modal::Closed`] with its id. When modals are open at once, the last
+/// in tree order takes the input.
+///
+///```rust
+/// let mut modal = Modal::new(dialog, Color(0, 0, 0, 120));
+/// modal.open();
+///```
+#[derive(Debug, Component, Clone)]
+pub struct Modal<D: Drawable + Clone + 'static>(
+    Stack, Portal<Option<Scrim>, ModalLayer<D>>, #[skip] Option<ModalLayer<D>>, #[skip] uuid::Uuid
+);
+
+impl<D: Drawable + Clone + 'static> Modal<D> {
+    /// Creates a closed modal with a backdrop of `scrim`.
+    pub fn new(dialog: D, scrim: Color) -> Self {
+        let id = uuid::Uuid::new_v4();
+        let stack = Stack(layout::Offset::Center, layout::Offset::Center, layout::Size::Fill, layout::Size::Fill, Padding::default());
+        let layer = ModalLayer(stack, Scrim(scrim), FocusScope::new(dialog), id);
+        Modal(Stack::default(), Portal::new(None, Placement::Over), Some(layer), id)
+    }
+
+    /// The id carried by the [`event::Modal::Closed`] this modal emits.
+    pub fn id(&self) -> uuid::Uuid {self.3}
+    pub fn is_open(&self) -> bool {self.1.is_open()}
+
+    pub fn open(&mut self) {
+        if let Some(layer) = self.2.take() {self.1.open(layer);}
+    }
+
+    pub fn close(&mut self) {
+        if let Some(layer) = self.1.close() {self.2 = Some(layer);}
+    }
+
+    pub fn inner(&mut self) -> &mut D {
+        match &mut self.2 {
+            Some(layer) => layer.2.inner(),
+            None => self.1.layer().expect("an open modal holds its layer").2.inner(),
+        }
+    }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Modal<D> {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        // Registered from here rather than the layer, which only sees ticks once the portal has drawn it.
+        if event.is::<TickEvent>() && self.is_open() {ctx.state.get_or_default::<Modals>().push(self.3);}
+        let Some(captured) = event.downcast_ref::<Captured>() else {return vec![event]};
+        if ctx.state.get_or_default::<Modals>().top() != Some(self.3) {return vec![event];}
+        let close = match captured {
+            Captured::Keyboard(KeyboardEvent{key: Key::Escape, state: KeyboardState::Pressed, ..}) => true,
+            Captured::Mouse(MouseEvent{position: Some(p), state: MouseState::Pressed(_)}) => {
+                let dialog = sized.1.first().and_then(|(_, portal)| portal.1.get(1)).and_then(|(_, layer)| layer.1.get(1));
+                !dialog.is_some_and(|(offset, dialog)| {
+                    p.0 > offset.0 && p.0 < offset.0 + dialog.0.0 && p.1 > offset.1 && p.1 < offset.1 + dialog.0.1
+                })
+            },
+            _ => false,
+        };
+        if !close {return vec![event];}
+        self.close();
+        ctx.emit(event::Modal::Closed(self.3));
+        vec![]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ExpanderLayout(f32);
+
+impl Layout for ExpanderLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let child = children[0];
+        let height = child.min_height() * self.0;
+        SizeRequest::new(child.min_width(), height, child.max_width(), height)
+    }
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        vec![Area{offset: (0.0, 0.0), size: (children[0].get(size).0, size.1)}]
+    }
+}
+
+/// A container that reveals its child by growing from no height to the child's requested height.
+///
+/// The child keeps its full size while animating and is clipped to the revealed area.
+#[derive(Debug, Component, Clone)]
+pub struct Expander<D: Drawable + Clone + 'static>(ExpanderLayout, Clip<D>, #[skip] bool, #[skip] Animated<f32>);
+
+impl<D: Drawable + Clone + 'static> Expander<D> {
+    /// Creates an [`Expander`] that opens and closes along `curve`.
+    pub fn new(child: D, open: bool, curve: Curve) -> Self {
+        let height = if open {1.0} else {0.0};
+        Expander(ExpanderLayout(height), Clip(child), open, Animated::new(height, curve))
+    }
+
+    pub fn open(&mut self) {self.2 = true;}
+    pub fn close(&mut self) {self.2 = false;}
+    pub fn toggle(&mut self) {self.2 = !self.2;}
+    pub fn is_open(&self) -> bool {self.2}
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1.0}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Expander<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_some() {
+            let target = if self.2 {1.0} else {0.0};
+            if *self.3.target() != target {self.3.animate_to(target);}
+            if self.3.tick(ctx.delta()) {self.0.0 = self.3.get().max(0.0);}
+            if self.3.is_running() {ctx.request_frame();}
+        }
+        vec![event]
     }
 }
@@ -1,11 +1,14 @@
 use std::fmt::Debug;
 use std::any::Any;
+use std::cell::RefCell;
 
 use crate::event::*;
 use crate::layout::{SizeRequest, Area, Layout};
 use crate::Context;
+use crate::accessibility::{AccessNode, Semantics, Role};
+use crate::i18n::Direction;
 
-use wgpu_canvas::{Instruction, Item, Shape, Image, Text};
+use wgpu_canvas::{Instruction, Item, Shape, Image, Text, Color};
 
 use downcast_rs::{Downcast, impl_downcast};
 pub use dyn_clone::{DynClone, clone_trait_object};
@@ -15,8 +18,60 @@ pub use prism_proc::Component;
 #[derive(Default, Debug, Clone)]
 pub struct RequestTree(pub SizeRequest, pub Vec<RequestTree>);
 
+/// The size given to a drawable and the offset and tree of each of its branches, along with how the branches
+/// are placed, found once here so pointer events can binary search them.
 #[derive(Default, Debug, Clone)]
-pub struct SizedTree(pub Size, pub Vec<(Offset, SizedTree)>);
+pub struct SizedTree(pub Size, pub Vec<(Offset, SizedTree)>, Sequence);
+
+/// How many emptied branch vectors of each tree kind are kept for reuse.
+const POOL: usize = 4096;
+
+thread_local! {
+    static REQUESTS: RefCell<Vec<Vec<RequestTree>>> = const {RefCell::new(Vec::new())};
+    static SIZES: RefCell<Vec<Vec<(Offset, SizedTree)>>> = const {RefCell::new(Vec::new())};
+}
+
+impl RequestTree {
+    /// Collects branches into a vector left over from an earlier frame, so laying out the same tree again
+    /// doesn't allocate.
+    pub fn branches(children: impl IntoIterator<Item = RequestTree>) -> Vec<RequestTree> {
+        let mut branches = REQUESTS.with_borrow_mut(Vec::pop).unwrap_or_default();
+        branches.extend(children);
+        branches
+    }
+
+    /// Empties the tree, keeping its vectors for [`RequestTree::branches`] to hand out again.
+    pub fn recycle(self) {
+        let mut pending = vec![self.1];
+        REQUESTS.with_borrow_mut(|pool| while let Some(mut branches) = pending.pop() {
+            pending.extend(branches.drain(..).map(|branch| branch.1).filter(|b| b.capacity() > 0));
+            if pool.len() < POOL {pool.push(branches);}
+        });
+    }
+}
+
+impl SizedTree {
+    pub fn new(size: Size, branches: Vec<(Offset, SizedTree)>) -> Self {
+        let sequence = Sequence::of(branches.iter().map(|(offset, branch)| Area{offset: *offset, size: branch.0}));
+        SizedTree(size, branches, sequence)
+    }
+
+    /// Collects branches into a vector left over from an earlier frame, see [`RequestTree::branches`].
+    pub fn branches(children: impl IntoIterator<Item = (Offset, SizedTree)>) -> Vec<(Offset, SizedTree)> {
+        let mut branches = SIZES.with_borrow_mut(Vec::pop).unwrap_or_default();
+        branches.extend(children);
+        branches
+    }
+
+    /// Empties the tree, keeping its vectors for [`SizedTree::branches`] to hand out again.
+    pub fn recycle(self) {
+        let mut pending = vec![self.1];
+        SIZES.with_borrow_mut(|pool| while let Some(mut branches) = pending.pop() {
+            pending.extend(branches.drain(..).map(|(_, branch)| branch.1).filter(|b| b.capacity() > 0));
+            if pool.len() < POOL {pool.push(branches);}
+        });
+    }
+}
 
 pub type Offset = (f32, f32);
 pub type Rect = (f32, f32, f32, f32);
@@ -31,7 +86,7 @@ pub trait Drawable: DynClone + Debug + Any + Downcast {
     fn request_size(&self) -> RequestTree;
 
     fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
-        SizedTree(request.0.get(size), vec![])
+        SizedTree::new(request.0.get(size), vec![])
     }
 
     fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction>;
@@ -39,6 +94,13 @@ pub trait Drawable: DynClone + Debug + Any + Downcast {
     fn name(&self) -> String {std::any::type_name_of_val(self).to_string()}
 
     fn event(&mut self, _ctx: &mut Context, _sized: &SizedTree, _event: Box<dyn Event>) {}
+
+    /// Describes this drawable and its descendants to assistive technologies, placed on screen at `offset`.
+    fn accessibility(&self, _sized: &SizedTree, _offset: Offset) -> Vec<AccessNode> {vec![]}
+
+    /// Returns the drawables directly below this one, each with the index of its branch in this drawable's
+    /// [`RequestTree`] and [`SizedTree`], or `None` when it shares them.
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![]}
 }
 
 clone_trait_object!(Drawable);
@@ -58,6 +120,12 @@ impl Drawable for Box<dyn Drawable> {
     fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
         Drawable::event(&mut **self, ctx, sized, event)
     }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        Drawable::accessibility(&**self, sized, offset)
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {Drawable::inspect(&**self)}
 }
 
 impl<D: Drawable + Debug + Any + Clone> Drawable for Option<D> {
@@ -78,6 +146,137 @@ impl<D: Drawable + Debug + Any + Clone> Drawable for Option<D> {
     }
 
     fn name(&self) -> String { self.as_ref().map(|d| Drawable::name(d)).unwrap_or("None".to_string()) }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        self.as_ref().map(|d| Drawable::accessibility(d, sized, offset)).unwrap_or_default()
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {
+        self.as_ref().map(|d| vec![(None, d as &dyn Drawable)]).unwrap_or_default()
+    }
+}
+
+/// Draws its child with the alpha of every color scaled by an opacity between `0.0` and `1.0`.
+///
+/// Images without a tint color are drawn as-is since they carry no color to fade.
+#[derive(Debug, Clone)]
+pub struct Opacity<D: Drawable + Clone>(pub f32, pub D);
+
+impl<D: Drawable + Clone + 'static> Drawable for Opacity<D> {
+    fn request_size(&self) -> RequestTree {self.1.request_size()}
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.1.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        if self.0 <= 0.0 {return vec![];}
+        let mut instructions = self.1.draw(sized, offset, bound);
+        if self.0 < 1.0 {
+            let fade = |color: &mut Color| color.3 = (color.3 as f32 * self.0) as u8;
+            instructions.iter_mut().for_each(|Instruction(_, item)| {
+                if let Item::Shape(shape) = item {
+                    fade(&mut shape.color);
+                } else if let Item::Text(text) = item {
+                    text.spans.iter_mut().for_each(|span| fade(&mut span.color));
+                } else if let Item::Image(Image{color: Some(color), ..}) = item {
+                    fade(color);
+                }
+            });
+        }
+        instructions
+    }
+
+    fn name(&self) -> String {self.1.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        self.1.event(ctx, sized, event)
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        if self.0 <= 0.0 {return vec![];}
+        self.1.accessibility(sized, offset)
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.1)]}
+}
+
+/// Returns the part of `bound` covered by a drawable of the given size at the given offset.
+pub(crate) fn intersect(bound: Rect, offset: Offset, size: Size) -> Rect {
+    let x = bound.0.max(offset.0);
+    let y = bound.1.max(offset.1);
+    (
+        x, y,
+        ((bound.0 + bound.2).min(offset.0 + size.0) - x).max(0.0),
+        ((bound.1 + bound.3).min(offset.1 + size.1) - y).max(0.0)
+    )
+}
+
+/// Draws its child clipped to the space allotted to it.
+///
+/// The child is always built at its own requested size, so a parent can give a `Clip` less space
+/// than the child needs and only the part that fits is drawn.
+#[derive(Debug, Clone)]
+pub struct Clip<D: Drawable + Clone>(pub D);
+
+impl<D: Drawable + Clone + 'static> Drawable for Clip<D> {
+    fn request_size(&self) -> RequestTree {
+        let request = self.0.request_size();
+        RequestTree(request.0, vec![request])
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
+        let child = &request.1[0];
+        SizedTree::new(size, vec![((0.0, 0.0), self.0.build(child.0.get(size), child))])
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let bound = intersect(bound, offset, sized.0);
+        if bound.2 == 0.0 || bound.3 == 0.0 {return vec![];}
+        self.0.draw(&sized.1[0].1, offset, bound)
+    }
+
+    fn name(&self) -> String {self.0.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        self.0.event(ctx, &sized.1[0].1, event)
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        self.0.accessibility(&sized.1[0].1, offset)
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(Some(0), &self.0)]}
+}
+
+/// Gives its child a share of the free space in a [`Row`](crate::layout::Row) or [`Column`](crate::layout::Column)
+/// in proportion to `weight`, so children weighted `2.0` and `1.0` split it two to one.
+///
+/// Once any child is weighted, unweighted siblings stay at their minimum size. A child only grows as far as its
+/// own maximum size allows.
+///
+///```rust
+/// let layout = Row::start(8.0);
+/// let children = (Flexible(2.0, sidebar), Flexible(1.0, content));
+///```
+#[derive(Debug, Clone)]
+pub struct Flexible<D: Drawable + Clone>(pub f32, pub D);
+
+impl<D: Drawable + Clone + 'static> Drawable for Flexible<D> {
+    fn request_size(&self) -> RequestTree {
+        let request = self.1.request_size();
+        RequestTree(request.0.with_weight(self.0), request.1)
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.1.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {self.1.draw(sized, offset, bound)}
+
+    fn name(&self) -> String {self.1.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {self.1.event(ctx, sized, event)}
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {self.1.accessibility(sized, offset)}
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.1)]}
 }
 
 /// A composable UI element with children.
@@ -93,22 +292,22 @@ pub trait Component: Clone + Debug where Self: 'static {
 
 impl<C: Component + Clone + 'static + OnEvent> Drawable for C {
     fn request_size(&self) -> RequestTree {
-        let requests = self.children().into_iter().map(Drawable::request_size).collect::<Vec<_>>();
+        let requests = RequestTree::branches(self.children().into_iter().map(Drawable::request_size));
         let info = requests.iter().map(|i| i.0).collect::<Vec<_>>();
-        let r = self.layout().request_size(info);
+        let r = self.layout().request_size(&info);
         RequestTree(r, requests)
     }
 
     fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
         let size = request.0.get(size);
         let children = request.1.iter().map(|b| b.0).collect::<Vec<_>>();
-        SizedTree(
+        SizedTree::new(
             size,
-            self.layout().build(size, children).into_iter()
+            SizedTree::branches(self.layout().build(size, &children).into_iter()
             .zip(self.children()).zip(request.1.iter())
             .map(|((Area{offset, size}, child), branch)| {
                 (offset, child.build(size, branch))
-            }).collect()
+            }))
         )
     }
 
@@ -131,11 +330,22 @@ impl<C: Component + Clone + 'static + OnEvent> Drawable for C {
     fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
         let children = sized.1.iter().map(|(o, branch)| Area{offset: *o, size: branch.0}).collect::<Vec<_>>();
         for event in OnEvent::on_event(self, ctx, sized, event) {
-            event.pass(ctx, &children).into_iter().zip(self.children_mut()).zip(sized.1.iter()).for_each(
+            let passed = arranged(&children, sized.2, || event.pass(ctx, &children));
+            passed.into_iter().zip(self.children_mut()).zip(sized.1.iter()).for_each(
                 |((e, child), branch)| if let Some(e) = e {child.event(ctx, &branch.1, e);}
             );
         }
     }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        sized.1.iter().zip(self.children()).flat_map(|((o, branch), child)| {
+            child.accessibility(branch, (offset.0 + o.0, offset.1 + o.1))
+        }).collect()
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {
+        self.children().into_iter().enumerate().map(|(i, child)| (Some(i), child)).collect()
+    }
 }
 
 #[macro_export]
@@ -150,7 +360,7 @@ macro_rules! drawables {
 }
 
 macro_rules! impl_drawable {
-    ( $( $n:ident: $( $x:expr )* ),* ) => {
+    ( $( $n:ident: $x:expr, $a:expr ),* ) => {
         $(
             impl Drawable for $n {
                 fn request_size(&self) -> RequestTree {
@@ -158,15 +368,36 @@ macro_rules! impl_drawable {
                 }
 
                 fn draw(&self, _sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
-                    vec![Instruction(wgpu_canvas::Area{offset, bounds: Some(bound)}, ($( $x )*)(self))]
+                    vec![Instruction(wgpu_canvas::Area{offset, bounds: Some(bound)}, ($x)(self))]
+                }
+
+                fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+                    ($a)(self).map(|semantics| AccessNode{semantics, bounds: (offset.0, offset.1, sized.0.0, sized.0.1), children: vec![]})
+                        .into_iter().collect()
                 }
             }
         )*
     };
 }
 impl_drawable!(
-    Item: |s: &Item| s.clone(),
-    Shape: |s: &Shape| Item::Shape(*s),
-    Text: |s: &Text| Item::Text(s.clone()),
-    Image: |s: &Image| Item::Image(s.clone())
+    Item: |s: &Item| s.clone(), |_: &Item| None,
+    Shape: |s: &Shape| Item::Shape(*s), |_: &Shape| None,
+    Image: |s: &Image| Item::Image(s.clone()), |_: &Image| None
 );
+
+/// Text is measured and drawn with the characters its fonts lack split off into spans of the font fallback chain.
+impl Drawable for Text {
+    fn request_size(&self) -> RequestTree {
+        RequestTree(SizeRequest::fixed(crate::font::fall_back(self).size()), vec![])
+    }
+
+    fn draw(&self, _sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let text = Direction::current().align(crate::font::fall_back(self).into_owned());
+        vec![Instruction(wgpu_canvas::Area{offset, bounds: Some(bound)}, Item::Text(text))]
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        let semantics = Semantics{role: Role::Text, label: Some(self.spans.iter().map(|span| span.text.as_str()).collect()), ..Semantics::default()};
+        vec![AccessNode{semantics, bounds: (offset.0, offset.1, sized.0.0, sized.0.1), children: vec![]}]
+    }
+}
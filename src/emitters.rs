@@ -1,37 +1,216 @@
-use crate::event::{self, OnEvent, Key, Event, TickEvent, MouseEvent, MouseState, KeyboardEvent, KeyboardState, MouseButton};
-use crate::{events, Context};
-use crate::drawable::{Drawable, Component, SizedTree};
-use crate::layout::Stack;
+use crate::event::{self, OnEvent, Key, Event, TickEvent, MouseEvent, MouseState, KeyboardEvent, KeyboardState, MouseButton, TouchEvent};
+use crate::{events, Context, KeyboardKind, ResizeEdge};
+use crate::drawable::{Drawable, Component, SizedTree, Offset, Rect, DynClone, clone_trait_object};
+use crate::caret::{Caret, CaretMark};
+use crate::canvas::Text;
+use crate::text::TextBuffer;
+use std::fmt::Debug;
+use crate::layout::{Stack, Layout, Area, SizeRequest};
+use crate::touch::Touches;
+use std::sync::{Arc, Mutex};
+use crate::machine::Machine;
 use std::time::Duration;
 
 const TEXT_INPUT_UUID: uuid::Uuid = uuid::uuid!("123e4567-e89b-12d3-a456-426614174000");
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ButtonState {Idle, Hovered, Pressed}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ButtonInput {Enter, Leave, Press, Release(bool)}
+
+/// Whether `event` is Enter or Space going down or, with `pressed` false, coming back up.
+fn activation(event: &dyn Event, pressed: bool) -> Option<Key> {
+    match event.downcast_ref::<KeyboardEvent>()? {
+        KeyboardEvent{key: key @ (Key::Enter | Key::Space), state: KeyboardState::Pressed, ..} if pressed => Some(*key),
+        KeyboardEvent{key: key @ (Key::Enter | Key::Space), state: KeyboardState::Released, ..} if !pressed => Some(*key),
+        _ => None
+    }
+}
+
+/// Emits [`event::Button`] for presses and hovers of its child.
+///
+/// Inside a [`Focusable`](crate::focus::Focusable) that has focus, Enter and Space press it as a click would, with
+/// the press ending in a release over the button when the same key comes back up, so held keys don't press it again.
+/// Losing focus with the key still down ends the press as a release outside would.
 #[derive(Debug, Component, Clone)]
-pub struct Button<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] bool);
+pub struct Button<D: Drawable + Clone + 'static>(
+    Stack, pub D, #[skip] Machine<ButtonState, ButtonInput>, #[skip] bool, #[skip] Option<Key>, #[skip] bool
+);
 impl<D: Drawable + Clone + 'static> Button<D> {
-    pub fn new(child: D) -> Self {Button(Stack::default(), child, false)}
+    pub fn new(child: D) -> Self {
+        let released = if crate::IS_MOBILE {ButtonState::Idle} else {ButtonState::Hovered};
+        Button(Stack::default(), child, Machine::new(ButtonState::Idle)
+            .on(ButtonState::Idle, ButtonInput::Enter, ButtonState::Hovered)
+            .on(ButtonState::Hovered, ButtonInput::Leave, ButtonState::Idle)
+            .on_any(ButtonInput::Press, ButtonState::Pressed)
+            .on(ButtonState::Pressed, ButtonInput::Release(true), released)
+            .on(ButtonState::Pressed, ButtonInput::Release(false), ButtonState::Idle),
+            false, None, false
+        )
+    }
+
+    /// Feeds `input` to the machine, returning the events a button has always produced. Hover is tracked apart
+    /// from the press, so moving off a pressed button still ends the hover, and on desktop any release over the
+    /// button ends the press and hovers it. On mobile releasing over the button leaves the press for the app to end.
+    fn fire(&mut self, input: ButtonInput) -> Vec<Box<dyn Event>> {
+        let pressed = self.2.state() == ButtonState::Pressed;
+        self.2.fire(input);
+        match input {
+            ButtonInput::Press if pressed => vec![],
+            ButtonInput::Press => events![event::Button::Pressed(true)],
+            ButtonInput::Release(true) if crate::IS_MOBILE => vec![],
+            ButtonInput::Release(true) => {
+                self.5 = true;
+                events![event::Button::Pressed(false), event::Button::Hover(true)]
+            },
+            ButtonInput::Release(false) if pressed => events![event::Button::Pressed(false)],
+            ButtonInput::Release(false) => vec![],
+            ButtonInput::Enter | ButtonInput::Leave => {
+                let hovered = input == ButtonInput::Enter;
+                match std::mem::replace(&mut self.5, hovered) == hovered {
+                    true => vec![],
+                    false => events![event::Button::Hover(hovered)],
+                }
+            },
+        }
+    }
 }
 
 impl<D: Drawable + Clone + 'static> OnEvent for Button<D> {
-    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
-        if let Some(event) = event.downcast_ref::<MouseEvent>() {
-            match event.state {
-                MouseState::Pressed(MouseButton::Left) if event.position.is_some() => {
-                    self.2 = true;
-                    return events![event::Button::Pressed(true)];
+    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(mouse) = event.downcast_ref::<MouseEvent>() {
+            let input = match mouse.state {
+                MouseState::Pressed(MouseButton::Left) if mouse.position.is_some() => Some(ButtonInput::Press),
+                MouseState::Released(MouseButton::Left) => Some(ButtonInput::Release(mouse.position.is_some())),
+                MouseState::Moved | MouseState::Scroll(..) if !crate::IS_MOBILE => match mouse.position {
+                    Some(_) if crate::power::is_low_power() => None,
+                    Some(_) => Some(ButtonInput::Enter),
+                    None => Some(ButtonInput::Leave),
+                },
+                _ => None
+            };
+            if let Some(input) = input {return self.fire(input);}
+        } else if let Some(event::Focused(focused)) = event.downcast_ref::<event::Focused>() {
+            self.3 = *focused;
+            if !focused && self.4.take().is_some() {
+                let mut events = self.fire(ButtonInput::Release(false));
+                events.push(event);
+                return events;
+            }
+        } else if let Some(key) = activation(&*event, true).filter(|_| self.3 && self.4.is_none()) {
+            self.4 = Some(key);
+            return self.fire(ButtonInput::Press);
+        } else if let Some(key) = activation(&*event, false).filter(|key| self.4 == Some(*key)) {
+            self.4 = None;
+            return self.fire(ButtonInput::Release(true));
+        }
+        vec![event]
+    }
+}
+
+/// A region that moves the window when pressed, for building the titlebar of a frameless desktop window.
+///
+/// The move starts once a press has travelled a few pixels, so clicks still reach buttons in the titlebar.
+/// With [`WindowDragRegion::resizable`], presses within `margin` of an edge resize the window from that edge
+/// or corner instead.
+#[derive(Debug, Component, Clone)]
+pub struct WindowDragRegion<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] f32, #[skip] Option<(f32, f32)>);
+
+impl<D: Drawable + Clone + 'static> WindowDragRegion<D> {
+    pub fn new(child: D) -> Self {WindowDragRegion(Stack::default(), child, 0.0, None)}
+
+    pub fn resizable(mut self, margin: f32) -> Self {
+        self.2 = margin;
+        self
+    }
+
+    fn edge(&self, (x, y): (f32, f32), (width, height): (f32, f32)) -> Option<ResizeEdge> {
+        let (west, east) = (x < self.2, x > width - self.2);
+        let (north, south) = (y < self.2, y > height - self.2);
+        match (north, south, west, east) {
+            (true, _, true, _) => Some(ResizeEdge::NorthWest),
+            (true, _, _, true) => Some(ResizeEdge::NorthEast),
+            (_, true, true, _) => Some(ResizeEdge::SouthWest),
+            (_, true, _, true) => Some(ResizeEdge::SouthEast),
+            (true, ..) => Some(ResizeEdge::North),
+            (_, true, ..) => Some(ResizeEdge::South),
+            (.., true, _) => Some(ResizeEdge::West),
+            (.., true) => Some(ResizeEdge::East),
+            _ => None,
+        }
+    }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for WindowDragRegion<D> {
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() {
+            match (state, position) {
+                (MouseState::Pressed(MouseButton::Left), Some(position)) => match self.edge(*position, sized.0) {
+                    Some(edge) => ctx.start_window_resize(edge),
+                    None => self.3 = Some(*position),
                 },
-                MouseState::Moved | MouseState::Scroll(..) if !crate::IS_MOBILE => {
-                    return events![event::Button::Hover(event.position.is_some())];
+                (MouseState::Moved, Some(position)) => if let Some(start) = self.3
+                    && (position.0 - start.0).hypot(position.1 - start.1) > 4.0 {
+                    self.3 = None;
+                    ctx.start_window_drag();
                 },
-                MouseState::Released(MouseButton::Left) => {
-                    let result = match event.position.is_some() {
-                        true if !crate::IS_MOBILE => events![event::Button::Pressed(false), event::Button::Hover(true)],
-                        false if self.2 => events![event::Button::Pressed(false)],
-                        _ => vec![]
-                    };
+                (MouseState::Released(MouseButton::Left), _) => self.3 = None,
+                _ => {}
+            }
+        }
+        vec![event]
+    }
+}
 
-                    self.2 = false;
-                    return result;
+/// The payload being dragged, shared between every [`Draggable`] and [`DropTarget`] through [`Context::state`].
+///
+/// It outlives the release for the rest of that frame so targets later in the tree still see what was dropped.
+#[derive(Debug, Clone, Default)]
+struct Dragging {payload: Option<String>, released: bool}
+
+/// Drags a `String` payload once the pointer moves more than 4px while pressed, sending [`event::Drag::Moved`]
+/// to the child so it can follow the pointer.
+#[derive(Debug, Component, Clone)]
+pub struct Draggable<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] String, #[skip] Option<(f32, f32)>, #[skip] bool);
+
+impl<D: Drawable + Clone + 'static> Draggable<D> {
+    pub fn new(child: D, payload: impl Into<String>) -> Self {
+        Draggable(Stack::default(), child, payload.into(), None, false)
+    }
+
+    pub fn payload(&self) -> &str {&self.2}
+    pub fn set_payload(&mut self, payload: impl Into<String>) {self.2 = payload.into();}
+    pub fn is_dragging(&self) -> bool {self.4}
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Draggable<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.downcast_ref::<TickEvent>().is_some() {
+            let dragging = ctx.state.get_or_default::<Dragging>();
+            if dragging.released {*dragging = Dragging::default();}
+        }
+        // The pointer leaves the child while it is dragged, so distances come from the screen position instead.
+        let pointer = ctx.state.get_or_default::<crate::cursor::Cursor>().position();
+        if let Some(MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() {
+            match (state, position, pointer) {
+                (MouseState::Pressed(MouseButton::Left), Some(_), Some(pointer)) => self.3 = Some(pointer),
+                (MouseState::Moved, _, Some(pointer)) => if let Some(start) = self.3 {
+                    let moved = (pointer.0 - start.0, pointer.1 - start.1);
+                    if !self.4 && moved.0.hypot(moved.1) > 4.0 {
+                        self.4 = true;
+                        *ctx.state.get_or_default::<Dragging>() = Dragging{payload: Some(self.2.clone()), released: false};
+                        ctx.emit(event::Drag::Started(self.2.clone()));
+                    }
+                    if self.4 {return vec![Box::new(event::Drag::Moved(moved)), event];}
+                },
+                (MouseState::Released(MouseButton::Left), _, _) => {
+                    self.3 = None;
+                    if std::mem::take(&mut self.4) {
+                        ctx.state.get_or_default::<Dragging>().released = true;
+                        return vec![Box::new(event::Drag::Moved((0.0, 0.0))), event];
+                    }
                 },
                 _ => {}
             }
@@ -40,6 +219,45 @@ impl<D: Drawable + Clone + 'static> OnEvent for Button<D> {
     }
 }
 
+/// Accepts payloads from any [`Draggable`], sending [`event::Drag::Dropped`] with its id when one is released over it.
+///
+/// The child gets [`event::Drag::Entered`] and [`event::Drag::Left`] as a dragged payload moves over and off it.
+#[derive(Debug, Component, Clone)]
+pub struct DropTarget<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] String, #[skip] Option<String>);
+
+impl<D: Drawable + Clone + 'static> DropTarget<D> {
+    pub fn new(child: D, id: impl Into<String>) -> Self {
+        DropTarget(Stack::default(), child, id.into(), None)
+    }
+
+    pub fn id(&self) -> &str {&self.2}
+    /// The payload currently dragged over this target.
+    pub fn hovering(&self) -> Option<&str> {self.3.as_deref()}
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for DropTarget<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        let Some(MouseEvent{position, state}) = event.downcast_ref::<MouseEvent>() else {return vec![event]};
+        let Dragging{payload, released} = ctx.state.get_or_default::<Dragging>().clone();
+        match (state, payload) {
+            (MouseState::Released(MouseButton::Left), Some(payload)) if position.is_some() => {
+                ctx.emit(event::Drag::Dropped(payload, self.2.clone()));
+            },
+            (_, Some(payload)) if !released && position.is_some() && self.3.is_none() => {
+                self.3 = Some(payload.clone());
+                return vec![Box::new(event::Drag::Entered(payload)), event];
+            },
+            _ => {}
+        }
+        let over = position.is_some() && !released && !matches!(state, MouseState::Released(_));
+        match self.3.take_if(|_| !over || ctx.state.get_or_default::<Dragging>().payload.is_none()) {
+            Some(payload) => vec![Box::new(event::Drag::Left(payload)), event],
+            None => vec![event],
+        }
+    }
+}
+
 #[derive(Debug, Component, Clone)]
 pub struct NumericalInput<D: Drawable + Clone + 'static>(Stack, pub D);
 
@@ -74,16 +292,22 @@ impl<D: Drawable + Clone + 'static> OnEvent for NumericalInput<D> {
     }
 }
 
+/// Selects its child among the others sharing its group when pressed, or with Enter or Space while inside a
+/// focused [`Focusable`](crate::focus::Focusable).
 #[derive(Debug, Component, Clone)]
-pub struct Selectable<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] uuid::Uuid, #[skip] uuid::Uuid);
+pub struct Selectable<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] uuid::Uuid, #[skip] uuid::Uuid, #[skip] bool);
 impl<D: Drawable + Clone + 'static> Selectable<D> {
     pub fn new(child: D, group_id: uuid::Uuid) -> Self {
-        Selectable(Stack::default(), child, uuid::Uuid::new_v4(), group_id)
+        Selectable(Stack::default(), child, uuid::Uuid::new_v4(), group_id, false)
     }
 }
 impl<D: Drawable + Clone + 'static> OnEvent for Selectable<D> {
     fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
-        if let Some(MouseEvent {position: Some(_), state: MouseState::Pressed(MouseButton::Left)}) = event.downcast_ref::<MouseEvent>() {
+        if let Some(event::Focused(focused)) = event.downcast_ref::<event::Focused>() {
+            self.4 = *focused;
+        }
+        let key = self.4 && activation(&*event, true).is_some();
+        if key || matches!(event.downcast_ref::<MouseEvent>(), Some(MouseEvent {position: Some(_), state: MouseState::Pressed(MouseButton::Left)})) {
             ctx.emit(event::Selectable::Pressed(self.2.to_string(), self.3.to_string()));
         } else if let Some(event::Selectable::Pressed(id, group_id)) = event.downcast_ref::<event::Selectable>()
         && *group_id == self.3.to_string() {
@@ -123,43 +347,148 @@ impl<D: Drawable + Clone + 'static> OnEvent for Slider<D> {
     }
 }
 
+/// Finds the first [`Text`] within `drawable`, with its offset from `drawable`.
+fn find_text<'a>(drawable: &'a dyn Drawable, sized: &SizedTree, offset: Offset) -> Option<(&'a Text, Offset)> {
+    if let Some(text) = drawable.downcast_ref::<Text>() {return Some((text, offset));}
+    drawable.inspect().into_iter().find_map(|(branch, child)| match branch {
+        Some(index) => sized.1.get(index).and_then(|(o, tree)| find_text(child, tree, (offset.0 + o.0, offset.1 + o.1))),
+        None => find_text(child, sized, offset),
+    })
+}
+
+/// Lays the field's child out in a [`Stack`] and spreads its [`CaretMark`] beneath it, so the mark is drawn from
+/// the field's top left without changing its size.
+#[derive(Debug, Clone, Default)]
+struct CaretLayout(Stack);
+
+impl Layout for CaretLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {self.0.request_size(&children[1..])}
+
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        [Area{offset: (0.0, 0.0), size}].into_iter().chain(self.0.build(size, &children[1..])).collect()
+    }
+}
+
+/// An editable field that keeps its own [`TextBuffer`] and emits [`event::TextInput::Edited`] with the text and
+/// caret whenever they change.
+///
+/// Keys edit the text while focused, with Shift selecting, Ctrl or Alt moving by word and Ctrl+C and Ctrl+X
+/// copying and cutting the selection. Pressing places the caret at the nearest character of the first [`Text`]
+/// in the child, and dragging selects. The field claims the shared [`Caret`] on focus and on every edit, releases
+/// it on blur, and reports where the caret sits in that [`Text`] while it holds it.
 #[derive(Debug, Component, Clone)]
-pub struct TextInput<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] Option<bool>);
+pub struct TextInput<D: Drawable + Clone + 'static>(
+    CaretLayout, pub D, #[skip] Option<bool>, #[skip] KeyboardKind, #[skip] TextBuffer, #[skip] bool, #[skip] uuid::Uuid,
+    #[order(0)] CaretMark
+);
+
 impl<D: Drawable + Clone + 'static> TextInput<D> {
-    pub fn new(child: D, requires_focus: bool) -> Selectable<Self> {Selectable::new(TextInput(Stack::default(), child, requires_focus.then_some(false)), TEXT_INPUT_UUID)}
+    pub fn new(child: D, requires_focus: bool) -> Selectable<Self> {Self::with_keyboard(child, requires_focus, KeyboardKind::Text)}
+
+    /// Creates a [`TextInput`] that shows the `kind` on-screen keyboard while focused.
+    pub fn with_keyboard(child: D, requires_focus: bool, kind: KeyboardKind) -> Selectable<Self> {
+        Selectable::new(TextInput(
+            CaretLayout::default(), child, requires_focus.then_some(false), kind, TextBuffer::default(), false,
+            uuid::Uuid::new_v4(), CaretMark::default()
+        ), TEXT_INPUT_UUID)
+    }
+
+    pub fn buffer(&self) -> &TextBuffer {&self.4}
+
+    /// Replaces the text, moving the caret to its end. No [`event::TextInput::Edited`] is emitted.
+    pub fn set_text(&mut self, text: impl Into<String>) {self.4.set_text(text)}
+
+    fn focus(&mut self, ctx: &mut Context, focused: bool) -> Box<dyn Event> {
+        if let Some(focus) = &mut self.2 {*focus = focused;}
+        match focused {
+            true => {
+                ctx.show_keyboard(self.3);
+                ctx.move_caret(self.6);
+            },
+            false => {
+                ctx.hide_keyboard();
+                ctx.release_caret(self.6);
+                self.7.0 = None;
+            },
+        }
+        Box::new(event::TextInput::Focused(focused))
+    }
+
+    fn edited(&self, ctx: &mut Context) -> Box<dyn Event> {
+        ctx.move_caret(self.6);
+        Box::new(event::TextInput::Edited(self.4.text().to_string(), self.4.position()))
+    }
+
+    /// The caret index nearest to `position`, if the child draws any [`Text`].
+    fn caret_at(&self, sized: &SizedTree, position: (f32, f32)) -> Option<usize> {
+        let (offset, child) = sized.1.get(1)?;
+        let (text, at) = find_text(&self.1, child, *offset)?;
+        Some(crate::text::caret_at(text, position.0 - at.0))
+    }
+
+    /// Where the caret sits in the first [`Text`] of the child, from the top left of the field.
+    fn caret_rect(&self, sized: &SizedTree) -> Option<Rect> {
+        let (offset, child) = sized.1.get(1)?;
+        let (text, at) = find_text(&self.1, child, *offset)?;
+        let height = crate::font::fall_back(text).size().1.max(text.spans.first().map_or(0.0, |span| span.font_size));
+        Some((at.0 + crate::text::caret_offset(text, self.4.caret()), at.1, 1.0, height))
+    }
 }
 
 impl<D: Drawable + Clone + 'static> OnEvent for TextInput<D> {
-    fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
-        if let Some(event::Selectable::Selected(selected)) = event.downcast_ref::<event::Selectable>() {
-            if let Some(focus) = &mut self.2 {*focus = *selected;}
-            return vec![Box::new(event::TextInput::Focused(*selected)), event];
+    fn on_event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.is::<TickEvent>() {
+            let owned = ctx.state.get::<Caret>().and_then(Caret::owner) == Some(self.6);
+            self.7.0 = owned.then(|| self.caret_rect(sized)).flatten();
+        } else if let Some(event::Selectable::Selected(selected)) = event.downcast_ref::<event::Selectable>() {
+            return vec![self.focus(ctx, *selected), event];
         } else if let Some(e) = event.downcast_ref::<MouseEvent>() {
             let mut events: Vec<Box<dyn Event>> = Vec::new();
 
-            match e.state {
-                MouseState::Pressed(MouseButton::Left) if e.position.is_some() => {
-                    if let Some(focus) = &mut self.2 {*focus = true;}
-                    events.push(Box::new(event::TextInput::Focused(true)));
+            match (e.state, e.position) {
+                (MouseState::Pressed(MouseButton::Left), Some(position)) => {
+                    events.push(self.focus(ctx, true));
+                    if let Some(index) = self.caret_at(sized, position) {
+                        self.4.set_caret(index);
+                        self.5 = true;
+                        events.push(self.edited(ctx));
+                    }
                 }
-                MouseState::Pressed(MouseButton::Left) if e.position.is_none() && !crate::IS_MOBILE => { 
-                    if let Some(focus) = &mut self.2 {*focus = false;}
-                    events.push(Box::new(event::TextInput::Focused(false)));
+                (MouseState::Pressed(MouseButton::Left), None) if !crate::IS_MOBILE => { 
+                    events.push(self.focus(ctx, false));
+                },
+                (MouseState::Moved, Some(position)) if self.5 => {
+                    if let Some(index) = self.caret_at(sized, position) && index != self.4.caret() {
+                        self.4.move_caret(index, true);
+                        events.push(self.edited(ctx));
+                    }
                 },
-                MouseState::Moved | MouseState::Scroll(..) if !crate::IS_MOBILE && !self.2.unwrap_or_default() => {
-                    events.push(Box::new(event::TextInput::Hover(e.position.is_some())));
+                (MouseState::Released(MouseButton::Left), _) => self.5 = false,
+                (MouseState::Moved | MouseState::Scroll(..), position) if !crate::IS_MOBILE && !self.2.unwrap_or_default() => {
+                    events.push(Box::new(event::TextInput::Hover(position.is_some())));
                 }
                 _ => {}
             }
 
             events.push(event);
             return events;
-        } else if let Some(KeyboardEvent { state: KeyboardState::Pressed, key, .. }) = event.downcast_ref() {
-            let key = *key;
-            if let Some(focus) = self.2 {
-                return if focus { vec![event, Box::new(event::TextInput::Edited(key))] } else { Vec::new() };
+        } else if let Some(KeyboardEvent { state: KeyboardState::Pressed | KeyboardState::Repeated, key, modifiers }) = event.downcast_ref() {
+            if self.2 == Some(false) {return Vec::new();}
+            let (key, modifiers) = (*key, *modifiers);
+            let shortcut = modifiers.control || modifiers.supermeta;
+            if shortcut && matches!(key, Key::Character('c' | 'C' | 'x' | 'X')) && self.4.has_selection() {
+                ctx.set_clipboard(self.4.selected().to_string());
+                if matches!(key, Key::Character('c' | 'C')) {return vec![event];}
             }
-            return vec![event, Box::new(event::TextInput::Edited(key))];
+            let before = self.4.position();
+            let changed = match key {
+                Key::Character('x' | 'X') if shortcut => self.4.delete_selection(),
+                _ => self.4.apply_with(key, modifiers),
+            };
+            return match changed || self.4.position() != before {
+                true => vec![event, self.edited(ctx)],
+                false => vec![event],
+            };
         }
 
         vec![event]
@@ -167,24 +496,28 @@ impl<D: Drawable + Clone + 'static> OnEvent for TextInput<D> {
 }
 
 #[derive(Debug, Component, Clone)]
-pub struct Scrollable<D: Drawable + Clone + PartialEq + 'static>(Stack, pub Momentum<D>, #[skip] (f32, f32));
+pub struct Scrollable<D: Drawable + Clone + 'static>(Stack, pub Momentum<D>, #[skip] (f32, f32));
 
-impl<D: Drawable + Clone + PartialEq + 'static> Scrollable<D> {
+impl<D: Drawable + Clone + 'static> Scrollable<D> {
     pub fn new(child: D) -> Self {
-        Scrollable(Stack::default(), Momentum::new(child), (0.0, 0.0))
+        Self::with_physics(child, Friction::default())
+    }
+
+    pub fn with_physics(child: D, physics: impl ScrollPhysics + 'static) -> Self {
+        Scrollable(Stack::default(), Momentum::with_physics(child, physics), (0.0, 0.0))
     }
 }
 
-impl<D: Drawable + Clone + PartialEq + 'static> std::ops::Deref for Scrollable<D> {
+impl<D: Drawable + Clone + 'static> std::ops::Deref for Scrollable<D> {
     type Target = Momentum<D>;
     fn deref(&self) -> &Self::Target {&self.1}
 }
 
-impl<D: Drawable + Clone + PartialEq + 'static> std::ops::DerefMut for Scrollable<D> {
+impl<D: Drawable + Clone + 'static> std::ops::DerefMut for Scrollable<D> {
     fn deref_mut(&mut self) -> &mut Self::Target {&mut self.1}
 }
 
-impl<D: Drawable + Clone + PartialEq + 'static> OnEvent for Scrollable<D> {
+impl<D: Drawable + Clone + 'static> OnEvent for Scrollable<D> {
     fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
         if let Some(MouseEvent { position: Some(position), state, .. }) = event.downcast_ref::<event::MouseEvent>() {
             match state {
@@ -209,81 +542,278 @@ impl<D: Drawable + Clone + PartialEq + 'static> OnEvent for Scrollable<D> {
     }
 }
 
+/// How a [`Momentum`] keeps scrolling after a touch is lifted and comes to rest.
+///
+/// Velocities are in logical pixels per second, positive when the content scrolls towards its end.
+pub trait ScrollPhysics: DynClone + Debug {
+    /// Returns the velocity to coast at when a drag of `dragged` pixels is released at `velocity`.
+    fn release(&self, _dragged: f32, velocity: f32) -> f32 {velocity}
+
+    /// Returns the velocity after coasting at `velocity` for `delta`.
+    fn step(&self, velocity: f32, delta: Duration) -> f32;
+
+    /// The speed below which coasting stops.
+    fn rest(&self) -> f32 {10.0}
+}
+
+clone_trait_object!(ScrollPhysics);
+
+/// Coasting that loses a fixed fraction of its speed every second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Friction {
+    /// The fraction of speed left after a second of coasting.
+    pub decay: f32,
+}
+
+impl Friction {
+    /// The long glide of iOS.
+    pub const IOS: Friction = Friction{decay: 0.13};
+    /// The shorter glide of Android.
+    pub const ANDROID: Friction = Friction{decay: 0.03};
+
+    /// How far coasting from `velocity` travels before stopping.
+    pub fn distance(&self, velocity: f32) -> f32 {velocity / -self.decay.ln()}
+}
+
+impl Default for Friction {
+    fn default() -> Self {if cfg!(target_os = "android") {Friction::ANDROID} else {Friction::IOS}}
+}
+
+impl ScrollPhysics for Friction {
+    fn step(&self, velocity: f32, delta: Duration) -> f32 {velocity * self.decay.powf(delta.as_secs_f32())}
+}
+
+/// Coasting that always comes to rest a whole number of pages from where the drag started, for carousels and pagers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Paged {
+    pub page: f32,
+    pub friction: Friction,
+}
+
+impl Paged {
+    pub fn new(page: f32) -> Self {Paged{page, friction: Friction::default()}}
+}
+
+impl ScrollPhysics for Paged {
+    fn release(&self, dragged: f32, velocity: f32) -> f32 {
+        if self.page <= 0.0 {return velocity;}
+        let landing = dragged + self.friction.distance(velocity);
+        let target = (landing / self.page).round().clamp(-1.0, 1.0) * self.page;
+        (target - dragged) * -self.friction.decay.ln()
+    }
+
+    fn step(&self, velocity: f32, delta: Duration) -> f32 {self.friction.step(velocity, delta)}
+
+    fn rest(&self) -> f32 {1.0}
+}
+
+/// Turns touch drags into scroll events and keeps scrolling after the touch is lifted, as its [`ScrollPhysics`] describe.
 #[derive(Debug, Component, Clone)]
 pub struct Momentum<D: Drawable + Clone + 'static> {
     layout: Stack,
     pub inner: D,
-    #[skip] touching: bool,
-    #[skip] start_touch: Option<(f32, f32)>,
+    #[skip] physics: Box<dyn ScrollPhysics>,
+    /// The last touch position while touching.
+    #[skip] touch: Option<(f32, f32)>,
     #[skip] mouse: (f32, f32),
-    #[skip] scroll: Option<(f32, f32)>,
-    #[skip] time: Option<Duration>,
-    #[skip] speed: Option<f32>,
+    /// How far the content has been dragged since the touch began.
+    #[skip] dragged: f32,
+    /// How far the content has been dragged since the last tick.
+    #[skip] moved: f32,
+    #[skip] velocity: f32,
+    #[skip] coasting: bool,
 }
 
 impl<D: Drawable + Clone + 'static> Momentum<D> {
-    pub fn new(child: D) -> Self { 
+    pub fn new(child: D) -> Self {Self::with_physics(child, Friction::default())}
+
+    pub fn with_physics(child: D, physics: impl ScrollPhysics + 'static) -> Self {
         Momentum {
             layout: Stack::default(),
             inner: child,
-            touching: false,
-            start_touch: None,
+            physics: Box::new(physics),
+            touch: None,
             mouse: (0.0, 0.0),
-            scroll: None,
-            time: None,
-            speed: None,
+            dragged: 0.0,
+            moved: 0.0,
+            velocity: 0.0,
+            coasting: false,
         }
     }
+
+    pub fn set_physics(&mut self, physics: impl ScrollPhysics + 'static) {self.physics = Box::new(physics);}
+
+    /// Stops any coasting in progress.
+    pub fn stop(&mut self) {
+        self.coasting = false;
+        self.velocity = 0.0;
+    }
 }
 
 impl<D: Drawable + Clone + 'static> OnEvent for Momentum<D> {
-    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { 
-        if crate::IS_MOBILE {
-            if let Some(MouseEvent { position: Some(position), state, .. }) = event.downcast_ref::<MouseEvent>() {
-                match state {
-                    MouseState::Pressed(MouseButton::Left) => {
-                        self.scroll = Some(*position);
-                        self.touching = true;
-                    }, 
-                    MouseState::Moved => {
-                        self.mouse = *position;
-                    }, 
-                    MouseState::Released(MouseButton::Left) => {
-                        self.touching = false;
-                    },
-                    MouseState::Scroll(..) => {
-                        self.scroll = Some(*position);
-                    }, 
-                    _ => {}
-                }
-                self.mouse = *position;
-            } else if event.downcast_ref::<TickEvent>().is_some() && !self.touching && let Some(time) = self.time {
-                match &mut self.speed {
-                    Some(speed) => {
-                        *speed *= 0.92;
-                        if speed.abs() < 0.1 {
-                            self.time = None;
-                            self.speed = None;
-                            self.start_touch = None;
-                            return vec![event];
-                        }
-                    }
-                    None => {
-                        let start_y = self.start_touch.unwrap_or((0.0, 0.0)).1;
-                        let end_y = self.scroll.unwrap_or((0.0, 0.0)).1;
-                        let y_traveled = end_y - start_y;
-                        let time_secs = time.as_secs_f32();
-                        self.speed = Some(-((y_traveled / time_secs) * 0.05));
-                    }
-                }
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if !crate::IS_MOBILE {return vec![event];}
+        if let Some(MouseEvent { position: Some(position), state, .. }) = event.downcast_ref::<MouseEvent>() {
+            match state {
+                MouseState::Pressed(MouseButton::Left) => {
+                    self.stop();
+                    self.touch = Some(*position);
+                    self.dragged = 0.0;
+                    self.moved = 0.0;
+                },
+                MouseState::Moved | MouseState::Scroll(..) => if let Some(last) = self.touch {
+                    self.moved += last.1 - position.1;
+                    self.touch = Some(*position);
+                },
+                MouseState::Released(MouseButton::Left) => if self.touch.take().is_some() {
+                    self.velocity = self.physics.release(self.dragged, self.velocity);
+                    self.coasting = self.velocity.abs() > self.physics.rest();
+                    if self.coasting {ctx.request_frame();}
+                },
+                _ => {}
+            }
+            self.mouse = *position;
+        } else if event.is::<TickEvent>() {
+            let seconds = ctx.delta().as_secs_f32();
+            if self.touch.is_some() && seconds > 0.0 {
+                self.velocity = self.velocity * 0.2 + (self.moved / seconds) * 0.8;
+                self.dragged += self.moved;
+                self.moved = 0.0;
+            } else if self.coasting {
+                self.velocity = self.physics.step(self.velocity, ctx.delta());
+                self.coasting = self.velocity.abs() > self.physics.rest();
+                ctx.emit(MouseEvent { position: Some(self.mouse), state: MouseState::Scroll(0.0, self.velocity * seconds)});
+                ctx.request_frame();
+            }
+        }
+        vec![event]
+    }
+}
 
-                if let Some(speed) = self.speed {
-                    let state = (speed.abs() > 0.01).then_some(MouseState::Scroll(0.0, speed));
-                    if let Some(s) = state {
-                        ctx.emit(MouseEvent { position: Some(self.mouse), state: s});
-                    }
-                }
+/// A scale and offset for zoomed content, where a point of the content at `p` is shown at `p * scale + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomTransform {
+    pub scale: f32,
+    pub offset: (f32, f32),
+}
+
+impl Default for ZoomTransform {
+    fn default() -> Self {ZoomTransform{scale: 1.0, offset: (0.0, 0.0)}}
+}
+
+impl ZoomTransform {
+    /// Multiplies the scale by `factor`, clamped to `limits`, keeping the content under `focal_point` in place.
+    /// Returns the factor the scale actually changed by once clamped.
+    pub fn zoom(&mut self, factor: f32, focal_point: (f32, f32), limits: (f32, f32)) -> f32 {
+        let scale = (self.scale * factor).clamp(limits.0, limits.1);
+        let ratio = scale / self.scale;
+        self.offset = (
+            focal_point.0 - (focal_point.0 - self.offset.0) * ratio,
+            focal_point.1 - (focal_point.1 - self.offset.1) * ratio,
+        );
+        self.scale = scale;
+        ratio
+    }
+
+    pub fn pan(&mut self, delta: (f32, f32)) {self.offset = (self.offset.0 + delta.0, self.offset.1 + delta.1);}
+
+    /// Where the content point `point` is shown.
+    pub fn apply(&self, point: (f32, f32)) -> (f32, f32) {
+        (point.0 * self.scale + self.offset.0, point.1 * self.scale + self.offset.1)
+    }
+
+    /// The content point shown at `point`, such as where a zoomed map was tapped.
+    pub fn invert(&self, point: (f32, f32)) -> (f32, f32) {
+        ((point.0 - self.offset.0) / self.scale, (point.1 - self.offset.1) / self.scale)
+    }
+
+    /// How large content of `size` is shown, for resizing an [`Image`](crate::canvas::Image) or shape that has a
+    /// fixed size.
+    pub fn scale_size(&self, size: (f32, f32)) -> (f32, f32) {(size.0 * self.scale, size.1 * self.scale)}
+
+    /// Keeps content the size of `viewport` covering it when zoomed in and inside it when zoomed out.
+    fn clamp(&mut self, viewport: (f32, f32)) {
+        let bound = |offset: f32, size: f32| {
+            let edge = size * (1.0 - self.scale);
+            offset.clamp(edge.min(0.0), edge.max(0.0))
+        };
+        self.offset = (bound(self.offset.0, viewport.0), bound(self.offset.1, viewport.1));
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ZoomLayout(Arc<Mutex<ZoomTransform>>);
+
+impl Layout for ZoomLayout {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {children[0]}
+
+    fn build(&self, size: (f32, f32), _children: &[SizeRequest]) -> Vec<Area> {
+        let mut transform = self.0.lock().unwrap();
+        transform.clamp(size);
+        vec![Area{offset: transform.offset, size: transform.scale_size(size)}]
+    }
+}
+
+/// Zooms its child with two finger pinches, or Ctrl+scroll on desktop, sending [`event::Zoom`] to it each time.
+///
+/// The child is laid out at the zoomed size and offset and clipped to the `PinchZoom`, so content that fills the
+/// space it is given, such as a map, zooms as is. Content with a fixed size is resized on [`event::Zoom`] with
+/// [`ZoomTransform::scale_size`]. Two fingers also pan the zoomed content as they move together.
+#[derive(Debug, Component, Clone)]
+pub struct PinchZoom<D: Drawable + Clone + 'static>(
+    ZoomLayout, pub D, #[skip] (f32, f32), #[skip] Option<(f32, (f32, f32))>, #[skip] bool
+);
+
+impl<D: Drawable + Clone + 'static> PinchZoom<D> {
+    /// Zooms between the child's own size and eight times it.
+    pub fn new(child: D) -> Self {
+        PinchZoom(ZoomLayout(Arc::new(Mutex::new(ZoomTransform::default()))), child, (1.0, 8.0), None, false)
+    }
+
+    /// Sets the smallest and largest scale.
+    pub fn limits(mut self, min: f32, max: f32) -> Self {
+        self.2 = (min, max);
+        self
+    }
+
+    pub fn inner(&mut self) -> &mut D {&mut self.1}
+    pub fn transform(&self) -> ZoomTransform {*self.0.0.lock().unwrap()}
+    pub fn set_transform(&mut self, transform: ZoomTransform) {*self.0.0.lock().unwrap() = transform;}
+    pub fn reset(&mut self) {self.set_transform(ZoomTransform::default())}
+
+    /// Zooms by `factor`, returning an [`event::Zoom`] with the factor left once clamped to the limits, if any.
+    fn zoom(&mut self, factor: f32, focal_point: (f32, f32)) -> Option<Box<dyn Event>> {
+        let ratio = self.0.0.lock().unwrap().zoom(factor, focal_point, self.2);
+        (ratio != 1.0).then(|| Box::new(event::Zoom{scale: ratio, focal_point}) as Box<dyn Event>)
+    }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for PinchZoom<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if let Some(KeyboardEvent{key, state, modifiers}) = event.downcast_ref::<KeyboardEvent>() {
+            self.4 = match key {
+                Key::Control => *state != KeyboardState::Released,
+                _ => modifiers.control,
+            };
+        } else if let Some(MouseEvent{position: Some(position), state: MouseState::Scroll(_, y)}) = event.downcast_ref::<MouseEvent>()
+            && self.4 {
+            return self.zoom((-y * 0.01).exp(), *position).into_iter().collect();
+        } else if let Some(TouchEvent{id, position: Some(position), ..}) = event.downcast_ref::<TouchEvent>() {
+            // Fingers off the child still count, so the pinch is measured from every finger on the screen.
+            let touches = ctx.state.get_or_default::<Touches>();
+            let pinch = touches.get(*id).zip(touches.spread()).zip(touches.centroid()).filter(|_| touches.len() >= 2);
+            let Some(((screen, spread), centroid)) = pinch else {
+                self.3 = None;
+                return vec![event];
+            };
+            let centroid = (centroid.0 - screen.0 + position.0, centroid.1 - screen.1 + position.1);
+            let mut events = vec![];
+            if let Some((last, from)) = self.3.replace((spread, centroid)) && last > 0.0 {
+                self.0.0.lock().unwrap().pan((centroid.0 - from.0, centroid.1 - from.1));
+                events.extend(self.zoom(spread / last, centroid));
             }
+            events.push(event);
+            return events;
         }
         vec![event]
     }
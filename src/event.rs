@@ -1,8 +1,10 @@
 use crate::layout::Area;
 use crate::Context;
 use crate::drawable::SizedTree;
+use crate::service::{Service, ServiceError};
 
 use std::fmt::Debug;
+use std::sync::Arc;
 use image::RgbaImage;
 
 use downcast_rs::{Downcast, impl_downcast};
@@ -13,12 +15,108 @@ pub trait OnEvent {
     fn on_event(&mut self, _ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> { vec![event] }
 }
 
+pub use prism_proc::Event;
+
 pub trait Event: Debug + Downcast {
     fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>>;
+
+    /// Whether only the drawable with focus should see this event, so a [`Focusable`](crate::focus::Focusable) without it drops the event.
+    fn focused_only(&self) -> bool {false}
+
+    /// The full name of the event's type, such as `prism2::event::MouseEvent`, found without formatting the event.
+    fn type_name(&self) -> &'static str {std::any::type_name::<Self>()}
 }
 impl_downcast!(Event);
 
+/// Passes a clone of `event` to every child, the strategy `#[derive(Event)]` uses by default.
+pub fn broadcast<E: Event + Clone>(event: Box<E>, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+    children.iter().map(|_| Some(event.clone() as Box<dyn Event>)).collect()
+}
+
+/// Returns the position each child should see: relative to the topmost child it falls inside, and `None` for the rest.
+pub fn positions(position: Option<(f32, f32)>, children: &[Area]) -> Vec<Option<(f32, f32)>> {
+    let mut positions = vec![None; children.len()];
+    if let Some(position) = position && let Some(index) = hit(position, children) {
+        let offset = children[index].offset;
+        positions[index] = Some((position.0 - offset.0, position.1 - offset.1));
+    }
+    positions
+}
+
+/// How a drawable's children are placed, found when its [`SizedTree`] is built.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Sequence {
+    #[default]
+    Overlapping,
+    /// One after another downwards, as columns and lists place them.
+    Down,
+    /// One after another across, as rows place them.
+    Across,
+}
+
+impl Sequence {
+    pub(crate) fn of(children: impl Iterator<Item = Area> + Clone) -> Self {
+        if children.clone().is_sorted_by(|a, b| b.offset.1 >= a.offset.1 + a.size.1) {
+            Sequence::Down
+        } else if children.is_sorted_by(|a, b| b.offset.0 >= a.offset.0 + a.size.0) {
+            Sequence::Across
+        } else {
+            Sequence::Overlapping
+        }
+    }
+}
+
+thread_local! {
+    /// The children being passed an event and how they are placed, so [`hit`] needn't check again, only set
+    /// during [`arranged`].
+    static ARRANGED: std::cell::Cell<Option<(*const Area, usize, Sequence)>> = const {std::cell::Cell::new(None)};
+}
+
+/// Runs `pass` with how `children` are placed recorded for [`hit`], and forgets it again once `pass` returns so
+/// no other list of children can be mistaken for them.
+pub(crate) fn arranged<T>(children: &[Area], sequence: Sequence, pass: impl FnOnce() -> T) -> T {
+    ARRANGED.set(Some((children.as_ptr(), children.len(), sequence)));
+    let passed = pass();
+    ARRANGED.set(None);
+    passed
+}
+
+/// Returns the index of the topmost child `position` falls inside.
+///
+/// Children placed one after another down or across, as columns, rows and lists place them, are binary searched
+/// so pointer moves over long lists stay cheap. Overlapping children are scanned from the top. How the children
+/// of a component are placed is found once when it is built, and only checked here for other children.
+pub fn hit(position: (f32, f32), children: &[Area]) -> Option<usize> {
+    let inside = |Area{offset, size}: &Area| position.0 > offset.0 && position.0 < offset.0 + size.0 &&
+        position.1 > offset.1 && position.1 < offset.1 + size.1;
+    let search = |start: fn(&Area) -> f32, point: f32| {
+        let index = children.partition_point(|area| start(area) < point).checked_sub(1)?;
+        inside(&children[index]).then_some(index)
+    };
+    let sequence = match ARRANGED.get() {
+        Some((at, len, sequence)) if std::ptr::eq(at, children.as_ptr()) && len == children.len() => sequence,
+        _ => Sequence::of(children.iter().copied()),
+    };
+    match sequence {
+        Sequence::Down => search(|area| area.offset.1, position.1),
+        Sequence::Across => search(|area| area.offset.0, position.0),
+        Sequence::Overlapping => children.iter().rposition(inside),
+    }
+}
+
+/// Passes `event` to every child while something has focus, and to none otherwise.
+pub fn to_focused<E: Event + Clone>(event: Box<E>, ctx: &Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+    match ctx.focus.is_focused() {
+        true => broadcast(event, children),
+        false => absorb(children),
+    }
+}
+
+/// Passes nothing on, for events meant only for the drawable they are sent to.
+pub fn absorb(children: &[Area]) -> Vec<Option<Box<dyn Event>>> {children.iter().map(|_| None).collect()}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Modifiers {
     pub shift:   bool,
     pub control: bool,
@@ -28,22 +126,26 @@ pub struct Modifiers {
 
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Key {
     Escape, Enter, Tab, Space,
     Up, Down, Left, Right,
-    Delete, Backspace, Home, End,
+    Delete, Backspace, Home, End, PageUp, PageDown,
     Shift, Control, Alt, SuperMeta,
     CapsLock, NumLock, ScrollLock,
     Character(char)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum KeyboardState{ Pressed, Repeated, Released }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseButton{ Left, Right, Middle }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseState {
     Pressed(MouseButton),
     Released(MouseButton),
@@ -51,71 +153,397 @@ pub enum MouseState {
     Moved
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Event)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[event(positional)]
 pub struct MouseEvent {
     pub position: Option<(f32, f32)>,
     pub state: MouseState
 }
 
-impl Event for MouseEvent {
-    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
-        let mut passed = false;
-        children.iter().rev().map(|Area { offset, size }| {
-            let position = self.position.and_then(|position| {
-                (!passed).then(|| {
-                    (position.0 > offset.0 && position.0 < offset.0 + size.0 &&
-                     position.1 > offset.1 && position.1 < offset.1 + size.1)
-                        .then(|| { passed = true; (position.0 - offset.0, position.1 - offset.1) })
-                }).flatten()
-            });
-            Some(Box::new(MouseEvent { position, state: self.state}) as Box<dyn Event>)
-        }).collect::<Vec<_>>().into_iter().rev().collect()
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TouchPhase { Began, Moved, Ended, Cancelled }
+
+/// A finger touching the screen, sent by touch screen hosts for every finger as it goes down, moves and lifts.
+///
+/// Each finger keeps its `id` from `Began` until `Ended` or `Cancelled`, and each event is passed to the child
+/// under that finger alone, so several fingers can be over different drawables at once. Hosts still send the
+/// first finger as [`MouseEvent`]s, which everything that only needs one pointer keeps handling.
+#[derive(Debug, Clone, PartialEq, Event)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[event(positional)]
+pub struct TouchEvent {
+    pub id: u64,
+    pub phase: TouchPhase,
+    pub position: Option<(f32, f32)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardEvent {
     pub key:       Key,
     pub state:     KeyboardState,
     pub modifiers: Modifiers,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PixelFormat { #[default] Rgba8, Bgra8, Nv12 }
+
+/// The pixels of a [`CameraFrame`], shared between every component the frame is passed to.
+#[derive(Debug, Clone)]
+pub enum FrameData {
+    Bytes(Arc<[u8]>),
+    /// A platform texture handle, for hosts that keep frames on the GPU.
+    Texture(u64),
+}
+
+/// A frame from a running [`Camera`](crate::Camera), emitted by the host at the rate asked for in
+/// [`FrameSettings`](crate::FrameSettings) through [`Instance::frame`](crate::Instance::frame).
+#[derive(Debug, Clone)]
+pub struct CameraFrame {
+    pub size: (u32, u32),
+    pub format: PixelFormat,
+    pub data: FrameData,
+}
+
+impl CameraFrame {
+    /// Returns the frame as an image, if it holds [`PixelFormat::Rgba8`] or [`PixelFormat::Bgra8`] bytes.
+    pub fn image(&self) -> Option<RgbaImage> {
+        let FrameData::Bytes(bytes) = &self.data else {return None};
+        let bytes = match self.format {
+            PixelFormat::Rgba8 => bytes.to_vec(),
+            PixelFormat::Bgra8 => bytes.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], p[3]]).collect(),
+            PixelFormat::Nv12 => return None,
+        };
+        RgbaImage::from_raw(self.size.0, self.size.1, bytes)
+    }
+}
+
+/// How a picked image must be turned to display upright, as given by its EXIF orientation tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Orientation {
+    #[default]
+    Up, UpMirrored, Down, DownMirrored,
+    LeftMirrored, Right, RightMirrored, Left
+}
+
+impl Orientation {
+    /// Reads an EXIF orientation value, treating anything outside `1..=8` as [`Orientation::Up`].
+    pub fn from_exif(value: u16) -> Self {
+        match value {
+            2 => Orientation::UpMirrored,
+            3 => Orientation::Down,
+            4 => Orientation::DownMirrored,
+            5 => Orientation::LeftMirrored,
+            6 => Orientation::Right,
+            7 => Orientation::RightMirrored,
+            8 => Orientation::Left,
+            _ => Orientation::Up,
+        }
+    }
+}
+
+/// A photo chosen in the photo picker, decoded but not yet turned upright.
+#[derive(Clone, Debug)]
+pub struct Photo {
+    pub image: RgbaImage,
+    pub orientation: Orientation,
+    pub name: Option<String>,
+}
+
+impl Photo {
+    /// Returns the image turned as its [`Orientation`] asks.
+    pub fn oriented(&self) -> RgbaImage {
+        use image::imageops::{rotate90, rotate180, rotate270, flip_horizontal, flip_vertical};
+        match self.orientation {
+            Orientation::Up => self.image.clone(),
+            Orientation::UpMirrored => flip_horizontal(&self.image),
+            Orientation::Down => rotate180(&self.image),
+            Orientation::DownMirrored => flip_vertical(&self.image),
+            Orientation::LeftMirrored => flip_horizontal(&rotate90(&self.image)),
+            Orientation::Right => rotate90(&self.image),
+            Orientation::RightMirrored => flip_horizontal(&rotate270(&self.image)),
+            Orientation::Left => rotate270(&self.image),
+        }
+    }
+}
+
+/// The photos chosen after [`Context::pick_photo`], emitted by the host once the picker closes.
+/// Empty if the user cancelled.
+#[derive(Clone, Debug)]
+pub struct PickedPhoto(pub Vec<Photo>);
+
+/// Emitted by the host when the app is opened from the [`Notification`](crate::Notification) with this id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotificationTapped(pub String);
+
+/// The device's position, emitted by the host after [`Context::get_location`] or [`Context::watch_location`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocationUpdate {
+    pub lat: f64,
+    pub lon: f64,
+    /// The radius of uncertainty in meters.
+    pub accuracy: f32,
+}
+
+/// The screenshot from [`Context::capture_screen`], `None` if permission was denied or capturing failed.
+#[derive(Clone, Debug)]
+pub struct ScreenCaptured(pub crate::CaptureTarget, pub Option<RgbaImage>);
+
+/// A file chosen in the file picker along with its contents.
 #[derive(Clone, Debug)]
-pub struct CameraFrame(pub RgbaImage);
+pub struct PickedFile {
+    pub name: String,
+    pub handle: crate::FileHandle,
+    pub data: Arc<[u8]>,
+}
 
+/// The files chosen after [`Context::pick_file`], empty if the user cancelled.
 #[derive(Clone, Debug)]
-pub struct PickedPhoto(pub RgbaImage);
+pub struct PickedFiles(pub Vec<PickedFile>);
+
+/// Where the file from [`Context::save_file`] was written, `None` if the user cancelled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileSaved(pub Option<crate::FileHandle>);
+
+/// Whether anything handled the url given to [`Context::open_url`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct UrlOpened(pub String, pub bool);
+
+/// Emitted by the host when a remote change to a cloud key collides with a local write that hadn't synced yet.
+/// The remote value has not been applied; the app should merge the two and set the result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CloudConflict {
+    pub key: String,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+/// Broadcast when the app enters or leaves low power mode, see [`power::LowPower`](crate::power::LowPower).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct LowPowerChanged(pub bool);
+
+/// Broadcast when the user has gone without input for one of the thresholds set with [`Context::set_idle_thresholds`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdleEntered(pub std::time::Duration);
+
+/// Broadcast on the first input after an [`IdleEntered`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdleExited;
+
+/// Emitted by the host whenever the window is minimized, maximized, made fullscreen, kept on top or gains or loses focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowStateChanged(pub crate::WindowState);
+
+/// Emitted by the host whenever the user's [`Appearance`](crate::Appearance) settings change.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppearanceChanged(pub crate::Appearance);
+
+/// Emitted by the host whenever the user's [`Locale`](crate::Locale) or timezone changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocaleChanged(pub crate::Locale);
+
+/// Emitted by the host whenever the device's [`PowerState`](crate::PowerState) changes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerChanged(pub crate::PowerState);
+
+/// Emitted by the host whenever the device's [`NetworkStatus`](crate::NetworkStatus) changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkChanged(pub crate::NetworkStatus);
+
+/// The outcome of [`Context::request_permission`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PermissionResult(pub crate::Permission, pub crate::PermissionStatus);
+
+/// Emitted by the host as Bluetooth LE scans, connections and characteristic reads progress.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Bluetooth {
+    Advertised(crate::bluetooth::Advertisement),
+    Connected(crate::bluetooth::Device),
+    Disconnected(crate::bluetooth::Device),
+    Value(crate::bluetooth::Characteristic, Vec<u8>),
+    /// A connection, read or write on the device failed, with the platform's reason.
+    Failed(crate::bluetooth::Device, String),
+}
+
+/// The clipboard text in answer to [`Context::request_clipboard`], `None` if the clipboard is empty or holds no text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClipboardContents(pub Option<String>);
+
+/// The value stored in cloud storage under a key, in answer to [`Context::request_cloud`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CloudValue(pub String, pub Option<String>);
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TickEvent;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Button { Pressed(bool), Hover(bool), Disable(bool) }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Selectable { Pressed(String, String), Selected(bool) }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Slider { Start(f32), Moved(f32) }
 
+/// Sent by [`Draggable`](crate::emitters::Draggable) and [`DropTarget`](crate::emitters::DropTarget) while a payload is dragged.
+///
+/// `Started` and `Dropped(payload, target)` go to the whole app, `Moved` to the dragged child with how far it has
+/// moved since the press, back to `(0.0, 0.0)` once released, and `Entered`/`Left` to the child of the target under it.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Drag { Started(String), Moved((f32, f32)), Dropped(String, String), Entered(String), Left(String) }
+
+/// Sent by a [`PinchZoom`](crate::emitters::PinchZoom) to its child each time a pinch or Ctrl+scroll zooms it, with
+/// the factor the scale changed by once clamped to its limits and the point it zoomed around, from the top left of
+/// the `PinchZoom`. A zoom held at a limit sends nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Zoom { pub scale: f32, pub focal_point: (f32, f32) }
+
+/// Sent by a [`TextInput`](crate::emitters::TextInput), `Edited` with its text and caret whenever a key or click changes either.
 #[derive(Debug, Clone)]
-pub enum TextInput { Hover(bool), Focused(bool), Edited(Key) }
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextInput { Hover(bool), Focused(bool), Edited(String, crate::text::CaretPos) }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum NumericalInput { Delete, Digit(char), Char(char) }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Drawer { Opened(bool) }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableView { Sort(usize, bool), Resized(usize, f32), Selected(usize) }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaginatedList { LoadMore(usize) }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Overlay { DismissRequested(uuid::Uuid) }
+
+/// Emitted with a [`Modal`](crate::display::Modal)'s id when Escape or a press on its backdrop closes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Modal { Closed(uuid::Uuid) }
+
+/// Sent to the child of a [`Focusable`](crate::focus::Focusable) when it gains or loses focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Focused(pub bool);
+
+/// Broadcast when the [`Timeline`](crate::animation::Timeline) with this id finishes playing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelineFinished(pub uuid::Uuid);
+
+/// Emitted when a scroll started from app code on the [`ScrollView`](crate::display::ScrollView) with this id settles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScrollFinished(pub uuid::Uuid);
+
+/// Broadcast when undo or redo of [`State`](crate::state::State) changes becomes available or unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryChanged { pub can_undo: bool, pub can_redo: bool }
+
+/// Broadcast with the output of a future started by [`Context::spawn`], tagged with the id `spawn` returned.
+#[derive(Debug, Clone)]
+pub struct Task<T>(pub uuid::Uuid, pub T);
+
+impl<T: Clone + Debug + 'static> Event for Task<T> {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Broadcast with the answer to a [`Service`](crate::service::Service) call, tagged with the id [`Context::call`] returned.
+pub struct ServiceResponse<S: Service>(pub uuid::Uuid, pub Result<S::Response, ServiceError>);
+
+impl<S: Service> Clone for ServiceResponse<S> {fn clone(&self) -> Self {ServiceResponse(self.0, self.1.clone())}}
+impl<S: Service> Debug for ServiceResponse<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(&format!("ServiceResponse<{}>", S::NAME)).field(&self.0).field(&self.1).finish()
+    }
+}
+
+impl<S: Service> Event for ServiceResponse<S> {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Broadcast when a watched value of type `T` in [`State`](crate::state::State) changes.
+pub struct StateChanged<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for StateChanged<T> {fn default() -> Self {StateChanged(std::marker::PhantomData)}}
+impl<T> Clone for StateChanged<T> {fn clone(&self) -> Self {StateChanged::default()}}
+impl<T> Debug for StateChanged<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StateChanged<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T: 'static> Event for StateChanged<T> {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
+/// Broadcast when the watched value stored under the [`Key`](crate::state::Key) with this name changes.
+pub struct KeyChanged<T>(pub &'static str, std::marker::PhantomData<fn() -> T>);
+
+impl<T> KeyChanged<T> {pub fn new(name: &'static str) -> Self {KeyChanged(name, std::marker::PhantomData)}}
+impl<T> Clone for KeyChanged<T> {fn clone(&self) -> Self {KeyChanged::new(self.0)}}
+impl<T> Debug for KeyChanged<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KeyChanged<{}>({:?})", std::any::type_name::<T>(), self.0)
+    }
+}
+
+impl<T: 'static> Event for KeyChanged<T> {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+    }
+}
+
 macro_rules! impl_event_all_children {
     ( $( $n:ident ),* ) => {
         $(
             impl Event for $n {
                 fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
-                    children.iter().map(|_| Some(self.clone() as Box<dyn Event>)).collect()
+                    broadcast(self, children)
                 }
-            }       
+            }
         )*
     };
 }
-impl_event_all_children!(KeyboardEvent, CameraFrame, PickedPhoto, TickEvent, Button, Selectable, Slider, TextInput, NumericalInput);
+impl_event_all_children!(KeyboardEvent, CameraFrame, PickedPhoto, ScreenCaptured, ClipboardContents, CloudValue, CloudConflict, NotificationTapped, LocationUpdate, PickedFiles, FileSaved, UrlOpened, PowerChanged, NetworkChanged, AppearanceChanged, WindowStateChanged, IdleEntered, IdleExited, LowPowerChanged, LocaleChanged, PermissionResult, Bluetooth, TickEvent, Button, Selectable, Slider, TextInput, NumericalInput, Drawer, TableView, PaginatedList, Overlay, Modal, Drag, Zoom, HistoryChanged, TimelineFinished, ScrollFinished, Focused);
 
 #[macro_export]
 macro_rules! events {
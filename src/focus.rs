@@ -0,0 +1,208 @@
+use std::cell::Cell;
+use std::ops::Range;
+
+use uuid::Uuid;
+
+use crate::canvas::{self, Instruction, Item, Shape, ShapeType};
+use crate::drawable::{Drawable, RequestTree, SizedTree, Size, Offset, Rect};
+use crate::event::{self, Event, TickEvent, MouseEvent, MouseState, MouseButton, KeyboardEvent, KeyboardState, Key};
+use crate::accessibility::AccessNode;
+use crate::theme::FocusRing;
+use crate::Context;
+
+thread_local! {
+    static RING: Cell<Option<(Offset, Size)>> = const {Cell::new(None)};
+}
+
+/// Which [`Focusable`] has focus and whether it got there from the keyboard, moved through Contexts by the Instance.
+#[derive(Debug, Default)]
+pub(crate) struct Focus {
+    /// Every focusable in tree order, collected during the frame's tick.
+    order: Vec<Uuid>,
+    focused: Option<Uuid>,
+    visible: bool,
+    /// Every trapping [`FocusScope`] in tree order with the part of `order` inside it, collected during the tick.
+    scopes: Vec<(Uuid, Range<usize>)>,
+    /// The trapping scopes present after the last tick, in the order they opened, each with the focus to restore
+    /// when it closes.
+    open: Vec<(Uuid, Option<Uuid>)>,
+}
+
+impl Focus {
+    pub(crate) fn start_frame(&mut self) {
+        self.order.clear();
+        self.scopes.clear();
+    }
+
+    fn enter_scope(&mut self, id: Uuid) -> usize {
+        self.scopes.push((id, self.order.len()..self.order.len()));
+        self.scopes.len() - 1
+    }
+
+    fn leave_scope(&mut self, index: usize) {self.scopes[index].1.end = self.order.len();}
+
+    /// Gives focus back for scopes that closed and moves it into scopes that opened, returning whether it moved.
+    pub(crate) fn end_tick(&mut self) -> bool {
+        let before = self.focused;
+        while let Some(index) = self.open.iter().rposition(|(id, _)| !self.scopes.iter().any(|(scope, _)| scope == id)) {
+            let (_, restore) = self.open.remove(index);
+            if index == self.open.len() {self.focused = restore.filter(|id| self.order.contains(id));}
+        }
+        for (id, range) in &self.scopes {
+            if self.open.iter().any(|(open, _)| open == id) {continue;}
+            self.open.push((*id, self.focused));
+            if !self.focused.is_some_and(|focused| self.order[range.clone()].contains(&focused)) {
+                self.focused = self.order[range.clone()].first().copied();
+            }
+        }
+        before != self.focused
+    }
+
+    pub(crate) fn is_focused(&self) -> bool {self.focused.is_some()}
+
+    pub(crate) fn set(&mut self, id: Option<Uuid>, visible: bool) {
+        self.focused = id;
+        self.visible = visible;
+    }
+
+    /// Moves focus to `id` with the ring showing, unless it is outside the last open trapping [`FocusScope`].
+    fn request(&mut self, id: Uuid) {
+        let range = self.scopes.last().map(|(_, range)| range.clone()).unwrap_or(0..self.order.len());
+        if self.order[range].contains(&id) {self.set(Some(id), true);}
+    }
+
+    /// Moves focus to the next focusable on Tab, or the previous on Shift+Tab, showing the ring. While a trapping
+    /// [`FocusScope`] is open only the focusables inside the last one are visited.
+    pub(crate) fn key(&mut self, event: &dyn Event) {
+        let Some(KeyboardEvent{key: Key::Tab, state: KeyboardState::Pressed | KeyboardState::Repeated, modifiers}) = event.downcast_ref::<KeyboardEvent>() else {return};
+        let range = self.scopes.last().map(|(_, range)| range.clone()).unwrap_or(0..self.order.len());
+        let order = &self.order[range];
+        if order.is_empty() {return;}
+        let count = order.len();
+        let current = self.focused.and_then(|id| order.iter().position(|o| *o == id));
+        let next = match (current, modifiers.shift) {
+            (None, false) => 0,
+            (None, true) => count - 1,
+            (Some(i), false) => (i + 1) % count,
+            (Some(i), true) => (i + count - 1) % count,
+        };
+        let next = order[next];
+        self.set(Some(next), true);
+    }
+
+    /// Draws the ring around the focused drawable's area as recorded during the last draw, if focus came from the keyboard.
+    pub(crate) fn ring(&self, style: FocusRing) -> Vec<Instruction> {
+        let Some((offset, size)) = RING.take() else {return vec![]};
+        if !self.visible {return vec![];}
+        let inset = style.offset + style.width;
+        let shape = Shape{
+            shape: ShapeType::RoundedRectangle(style.width, (size.0 + inset * 2.0, size.1 + inset * 2.0), 0.0, style.radius),
+            color: style.color
+        };
+        vec![Instruction(canvas::Area{offset: (offset.0 - inset, offset.1 - inset), bounds: None}, Item::Shape(shape))]
+    }
+}
+
+/// Asks the [`Focusable`] at `position` to take focus, for an assistive technology's
+/// [`Action::Focus`](crate::accessibility::Action::Focus).
+#[derive(Debug, Clone, Event)]
+#[event(positional)]
+pub(crate) struct FocusAt {pub(crate) position: Option<(f32, f32)>}
+
+/// A wrapper that makes its child reachable with Tab and Shift+Tab, in tree order.
+///
+/// The child is sent [`event::Focused`] when focus arrives or leaves. Focus given by the keyboard shows a ring
+/// styled by [`Theme::focus_ring`](crate::theme::Theme::focus_ring) over everything else on screen, while a press
+/// focuses without one.
+#[derive(Debug, Clone)]
+pub struct Focusable<D: Drawable + Clone>(pub D, Uuid, bool);
+
+impl<D: Drawable + Clone> Focusable<D> {
+    pub fn new(child: D) -> Self {Focusable(child, Uuid::new_v4(), false)}
+
+    pub fn is_focused(&self) -> bool {self.2}
+
+    pub fn inner(&mut self) -> &mut D {&mut self.0}
+}
+
+impl<D: Drawable + Clone + 'static> Drawable for Focusable<D> {
+    fn request_size(&self) -> RequestTree {self.0.request_size()}
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.0.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        if self.2 {RING.set(Some((offset, sized.0)));}
+        self.0.draw(sized, offset, bound)
+    }
+
+    fn name(&self) -> String {self.0.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        if event.is::<TickEvent>() {
+            ctx.focus.order.push(self.1);
+        } else if let Some(MouseEvent{position, state: MouseState::Pressed(MouseButton::Left)}) = event.downcast_ref::<MouseEvent>() {
+            match position {
+                Some(_) => ctx.focus.set(Some(self.1), false),
+                None if ctx.focus.focused == Some(self.1) => ctx.focus.set(None, false),
+                None => {}
+            }
+        } else if let Some(FocusAt{position: Some(_)}) = event.downcast_ref::<FocusAt>() {
+            ctx.focus.request(self.1);
+        }
+
+        let focused = ctx.focus.focused == Some(self.1);
+        if focused != self.2 {
+            self.2 = focused;
+            self.0.event(ctx, sized, Box::new(event::Focused(focused)));
+        }
+        if focused || !event.focused_only() {self.0.event(ctx, sized, event)}
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {self.0.accessibility(sized, offset)}
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.0)]}
+}
+
+/// A wrapper that keeps Tab and Shift+Tab cycling through the [`Focusable`]s inside it while it traps focus, for
+/// dialogs, sheets and drawers.
+///
+/// When it starts trapping, focus moves to its first focusable unless already inside, and when it stops or leaves
+/// the tree, focus goes back to whatever had it before. If several trap at once the last in tree order wins.
+#[derive(Debug, Clone)]
+pub struct FocusScope<D: Drawable + Clone>(pub D, Uuid, bool);
+
+impl<D: Drawable + Clone> FocusScope<D> {
+    /// Creates a scope that traps focus for as long as it is in the tree.
+    pub fn new(child: D) -> Self {FocusScope(child, Uuid::new_v4(), true)}
+
+    pub fn is_trapping(&self) -> bool {self.2}
+
+    pub fn set_trapping(&mut self, trapping: bool) {self.2 = trapping;}
+
+    pub fn inner(&mut self) -> &mut D {&mut self.0}
+}
+
+impl<D: Drawable + Clone + 'static> Drawable for FocusScope<D> {
+    fn request_size(&self) -> RequestTree {self.0.request_size()}
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {self.0.build(size, request)}
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {self.0.draw(sized, offset, bound)}
+
+    fn name(&self) -> String {self.0.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        match self.2 && event.is::<TickEvent>() {
+            true => {
+                let index = ctx.focus.enter_scope(self.1);
+                self.0.event(ctx, sized, event);
+                ctx.focus.leave_scope(index);
+            },
+            false => self.0.event(ctx, sized, event)
+        }
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {self.0.accessibility(sized, offset)}
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {vec![(None, &self.0)]}
+}
@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use crate::canvas::{self, Text, Span};
+
+thread_local! {
+    static CHAIN: RefCell<Vec<FontData>> = const {RefCell::new(Vec::new())};
+}
+
+/// Sets the fallback chain text is measured and drawn with this frame.
+pub(crate) fn start_frame(fonts: Option<&Fonts>) {
+    CHAIN.set(fonts.map(|fonts| fonts.chain().cloned().collect()).unwrap_or_default());
+}
+
+/// Splits the spans of `text` wherever their font lacks a character, giving those characters the first font in the
+/// fallback chain that covers and has them. Text whose fonts have every character is returned as it is.
+pub(crate) fn fall_back(text: &Text) -> Cow<'_, Text> {
+    let missing = |span: &Span| span.text.chars().any(|c| !span.font.has_glyph(c));
+    CHAIN.with_borrow(|chain| match chain.is_empty() || !text.spans.iter().any(missing) {
+        true => Cow::Borrowed(text),
+        false => {
+            let mut text = text.clone();
+            text.spans = text.spans.into_iter().flat_map(|span| match missing(&span) {
+                true => split(chain, span),
+                false => vec![span],
+            }).collect();
+            Cow::Owned(text)
+        }
+    })
+}
+
+fn split(chain: &[FontData], span: Span) -> Vec<Span> {
+    let mut spans: Vec<Span> = vec![];
+    let mut joined = false;
+    for c in span.text.chars() {
+        let sequence = joined || joins(c);
+        joined = c == '\u{200D}';
+        let font = match span.font.has_glyph(c) {
+            true => &span.font,
+            false => chain.iter().find(|font| font.coverage.covers(c) && font.font.has_glyph(c)).map_or(&span.font, |font| &font.font),
+        };
+        match spans.last_mut() {
+            Some(last) if sequence || Arc::ptr_eq(&last.font, font) => last.text.push(c),
+            _ => spans.push(Span{text: c.to_string(), font: font.clone(), ..span.clone()}),
+        }
+    }
+    spans
+}
+
+/// Whether `c` continues the emoji sequence before it rather than starting a new character.
+fn joins(c: char) -> bool {matches!(c, '\u{200D}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}')}
+
+/// Where the bytes of a font registered with [`Context::register_font`](crate::Context::register_font) come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    /// A TrueType or OpenType file, usually from `include_bytes!`.
+    Bytes(Vec<u8>),
+    /// An installed font looked up by family name through the [`Handler`](crate::Handler).
+    System(String),
+}
+
+/// The characters a font is used for when it appears in the fallback chain.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Coverage {
+    /// Every character, for the last font in the chain.
+    #[default]
+    All,
+    /// Latin, Greek and Cyrillic letters with punctuation.
+    Latin,
+    /// Chinese, Japanese and Korean scripts.
+    Cjk,
+    /// Emoji, their modifiers and the joiners and selectors between them.
+    Emoji,
+    Ranges(Vec<RangeInclusive<char>>),
+}
+
+impl Coverage {
+    pub fn covers(&self, c: char) -> bool {
+        match self {
+            Coverage::All => true,
+            Coverage::Latin => matches!(c, '\u{0}'..='\u{24F}' | '\u{370}'..='\u{52F}' | '\u{1E00}'..='\u{1FFF}' | '\u{2000}'..='\u{206F}'),
+            Coverage::Cjk => matches!(c,
+                '\u{1100}'..='\u{11FF}' | '\u{2E80}'..='\u{2FDF}' | '\u{3000}'..='\u{9FFF}' |
+                '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}' | '\u{20000}'..='\u{3134F}'
+            ),
+            Coverage::Emoji => is_emoji(c),
+            Coverage::Ranges(ranges) => ranges.iter().any(|range| range.contains(&c)),
+        }
+    }
+}
+
+/// Whether `c` is drawn from an emoji font, including the modifiers, joiners and selectors inside emoji sequences.
+pub fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{200D}' | '\u{20E3}' | '\u{FE0F}' | '\u{2300}'..='\u{23FF}' | '\u{2600}'..='\u{27BF}' | '\u{2B00}'..='\u{2BFF}' |
+        '\u{1F000}'..='\u{1FAFF}' | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// A registered font.
+#[derive(Debug, Clone)]
+pub struct FontData {
+    pub name: String,
+    pub bytes: Arc<Vec<u8>>,
+    /// The parsed font, drawn in place of a span's own font for the characters that font lacks.
+    pub font: Arc<canvas::Font>,
+    pub coverage: Coverage,
+}
+
+/// The fonts registered at runtime and the fallback chain text resolves through, kept in [`Context::state`](crate::Context::state).
+///
+/// Every [`Text`] is measured and drawn through the chain, so characters its spans' fonts lack are drawn with the
+/// first registered font that covers them instead of as missing glyphs.
+#[derive(Debug, Clone, Default)]
+pub struct Fonts {
+    fonts: HashMap<String, FontData>,
+    fallback: Vec<String>,
+}
+
+impl Fonts {
+    pub(crate) fn insert(&mut self, font: FontData) {
+        if !self.fallback.contains(&font.name) {self.fallback.push(font.name.clone());}
+        self.fonts.insert(font.name.clone(), font);
+    }
+
+    pub(crate) fn set_fallback(&mut self, chain: Vec<String>) {self.fallback = chain;}
+
+    pub fn get(&self, name: &str) -> Option<&FontData> {self.fonts.get(name)}
+
+    /// The registered fonts in fallback order.
+    pub fn chain(&self) -> impl Iterator<Item = &FontData> {self.fallback.iter().filter_map(|name| self.fonts.get(name))}
+
+    /// Returns the first font in the chain that covers `c`.
+    pub fn resolve(&self, c: char) -> Option<&FontData> {self.chain().find(|font| font.coverage.covers(c))}
+
+    /// Splits `text` into runs that each draw with a single font, for building a text's spans.
+    ///
+    /// Characters no font covers stay with the run before them, and emoji sequences are never split, so an emoji
+    /// joined from several code points draws as one glyph.
+    pub fn runs<'a>(&'a self, text: &str) -> Vec<(&'a FontData, String)> {
+        let mut runs: Vec<(&FontData, String)> = vec![];
+        let mut joined = false;
+        for c in text.chars() {
+            let sequence = joined || joins(c);
+            joined = c == '\u{200D}';
+            match (runs.last_mut(), self.resolve(c)) {
+                (Some((_, run)), _) if sequence => run.push(c),
+                (Some((font, run)), Some(resolved)) if font.name == resolved.name => run.push(c),
+                (_, Some(resolved)) => runs.push((resolved, c.to_string())),
+                (Some((_, run)), None) => run.push(c),
+                (None, None) => if let Some(first) = self.chain().next() {runs.push((first, c.to_string()))},
+            }
+        }
+        runs
+    }
+}
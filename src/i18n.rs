@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::canvas::{Text, Align};
+use crate::layout::Area;
+use crate::{ambient, Locale};
+
+/// The reading direction of a subtree, set with an [`Ambient`](crate::ambient::Ambient).
+///
+/// `Row` and `Wrap` lay children out from the right and `Offset::Start` means the right edge when it is
+/// [`Direction::Rtl`]. Without one, the direction follows the first language of the ambient [`Locale`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    /// Returns the direction in scope for the drawable being built or drawn.
+    pub fn current() -> Self {
+        ambient::get::<Direction>()
+            .or_else(|| locale().and_then(|l| l.languages.first().map(|language| Direction::of(language))))
+            .unwrap_or_default()
+    }
+
+    /// Returns the direction a BCP 47 language tag is written in.
+    pub fn of(language: &str) -> Self {
+        match primary(language) {
+            "ar" | "he" | "fa" | "ur" | "ps" | "sd" | "yi" | "dv" | "ug" | "ckb" => Direction::Rtl,
+            _ => Direction::Ltr,
+        }
+    }
+
+    /// Mirrors `areas` laid out left to right across `width` when this direction is right to left.
+    pub fn mirror(self, mut areas: Vec<Area>, width: f32) -> Vec<Area> {
+        if self == Direction::Rtl {
+            areas.iter_mut().for_each(|area| area.offset.0 = width - area.offset.0 - area.size.0);
+        }
+        areas
+    }
+
+    /// Swaps left and right alignment of `text` when this direction is right to left.
+    pub fn align(self, mut text: Text) -> Text {
+        if self == Direction::Rtl {
+            text.align = match text.align {
+                Align::Left => Align::Right,
+                Align::Right => Align::Left,
+                align => align,
+            };
+        }
+        text
+    }
+}
+
+/// Returns the [`Locale`] set for the drawable being built or drawn by the closest enclosing [`Ambient`](crate::ambient::Ambient).
+pub fn locale() -> Option<Locale> {ambient::get::<Locale>()}
+
+fn primary(language: &str) -> &str {language.split(['-', '_']).next().unwrap_or(language)}
+
+fn language() -> String {
+    locale().and_then(|l| l.languages.first().cloned()).unwrap_or("en".to_string())
+}
+
+/// The CLDR plural category of a count, which picks the form of a translated string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Plural {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl Plural {
+    /// Returns the category of `n` in `language`, covering the common families of plural rules.
+    pub fn of(language: &str, n: u64) -> Self {
+        let (n10, n100) = (n % 10, n % 100);
+        match primary(language) {
+            "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "tr" => Plural::Other,
+            "fr" | "pt" | "hi" | "bn" if n < 2 => Plural::One,
+            "ru" | "uk" | "be" | "sr" | "hr" | "bs" => match (n10, n100) {
+                (1, n100) if n100 != 11 => Plural::One,
+                (2..=4, n100) if !(12..=14).contains(&n100) => Plural::Few,
+                _ => Plural::Many,
+            },
+            "pl" => match (n, n10, n100) {
+                (1, _, _) => Plural::One,
+                (_, 2..=4, n100) if !(12..=14).contains(&n100) => Plural::Few,
+                _ => Plural::Many,
+            },
+            "cs" | "sk" => match n {
+                1 => Plural::One,
+                2..=4 => Plural::Few,
+                _ => Plural::Other,
+            },
+            "ar" => match (n, n100) {
+                (0, _) => Plural::Zero,
+                (1, _) => Plural::One,
+                (2, _) => Plural::Two,
+                (_, 3..=10) => Plural::Few,
+                (_, 11..=99) => Plural::Many,
+                _ => Plural::Other,
+            },
+            "he" => match n {
+                1 => Plural::One,
+                2 => Plural::Two,
+                _ => Plural::Other,
+            },
+            _ if n == 1 => Plural::One,
+            _ => Plural::Other,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Plural::Zero => "zero",
+            Plural::One => "one",
+            Plural::Two => "two",
+            Plural::Few => "few",
+            Plural::Many => "many",
+            Plural::Other => "other",
+        }
+    }
+}
+
+/// Formats `value` with `decimals` fractional digits and the grouping and decimal separators of the ambient [`Locale`].
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let (group, decimal) = match primary(&language()) {
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" => (".", ","),
+        "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "nb" | "fi" => ("\u{202f}", ","),
+        "hi" | "bn" => (",", "."),
+        _ => (",", "."),
+    };
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let mut grouped = String::new();
+    whole.chars().enumerate().for_each(|(i, c)| {
+        if i > 0 && (whole.len() - i) % 3 == 0 {grouped.push_str(group);}
+        grouped.push(c);
+    });
+    let sign = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {"-"} else {""};
+    match fraction.is_empty() {
+        true => format!("{sign}{grouped}"),
+        false => format!("{sign}{grouped}{decimal}{fraction}"),
+    }
+}
+
+/// Translated strings by language, looked up in the ambient [`Locale`]'s preferred languages.
+///
+/// Plural forms are stored under `key.one`, `key.few`, `key.other` and so on, and `{n}` in them is replaced by the count.
+///
+///```rust
+/// let messages = Messages::default()
+///     .add("en", "inbox.other", "{n} messages").add("en", "inbox.one", "{n} message")
+///     .add("pt", "inbox.other", "{n} mensagens").add("pt", "inbox.one", "{n} mensagem");
+/// let label = messages.plural("inbox", count);
+///```
+#[derive(Debug, Clone, Default)]
+pub struct Messages(HashMap<String, HashMap<String, String>>);
+
+impl Messages {
+    pub fn add(mut self, language: &str, key: &str, message: &str) -> Self {
+        self.0.entry(language.to_string()).or_default().insert(key.to_string(), message.to_string());
+        self
+    }
+
+    fn lookup(&self, key: &str) -> Option<(&str, &String)> {
+        let languages = locale().map(|l| l.languages).unwrap_or_default();
+        languages.iter().flat_map(|l| [l.as_str(), primary(l)]).chain(["en"])
+            .find_map(|l| self.0.get_key_value(l).and_then(|(l, table)| table.get(key).map(|m| (l.as_str(), m))))
+    }
+
+    /// Returns the translation of `key`, or `key` itself when no preferred language has one.
+    pub fn get(&self, key: &str) -> String {
+        self.lookup(key).map(|(_, message)| message.clone()).unwrap_or(key.to_string())
+    }
+
+    /// Returns the form of `key` for a count of `n`, falling back to `key.other`. The form is chosen by the rules of
+    /// the first preferred language with a `key.other`, so a fallback language is never given another's plural forms.
+    pub fn plural(&self, key: &str, n: u64) -> String {
+        self.lookup(&format!("{key}.other")).map(|(language, other)| {
+            let form = self.0[language].get(&format!("{key}.{}", Plural::of(language, n).key())).unwrap_or(other);
+            form.replace("{n}", &format_number(n as f64, 0))
+        }).unwrap_or(key.to_string())
+    }
+}
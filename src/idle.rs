@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use crate::event::{Event, IdleEntered, IdleExited, MouseEvent, KeyboardEvent, TouchEvent};
+
+/// How long the user has gone without pointer or keyboard input, kept in [`Context::state`](crate::Context::state).
+///
+/// Each threshold set with [`Context::set_idle_thresholds`](crate::Context::set_idle_thresholds) is announced
+/// once with an [`IdleEntered`] as inactivity passes it, such as dimming a kiosk after a minute and locking a
+/// wallet after five, and the next input announces [`IdleExited`].
+#[derive(Debug, Clone, Default)]
+pub struct Idle {
+    thresholds: Vec<Duration>,
+    elapsed: Duration,
+    /// How many thresholds have been passed.
+    reached: usize,
+}
+
+impl Idle {
+    /// The time since the last input.
+    pub fn elapsed(&self) -> Duration {self.elapsed}
+
+    pub fn is_idle(&self) -> bool {self.reached > 0}
+
+    pub(crate) fn set_thresholds(&mut self, mut thresholds: Vec<Duration>) {
+        thresholds.sort();
+        self.reached = thresholds.iter().filter(|t| **t <= self.elapsed).count();
+        self.thresholds = thresholds;
+    }
+
+    pub(crate) fn is_input(event: &dyn Event) -> bool {
+        event.is::<MouseEvent>() || event.is::<KeyboardEvent>() || event.is::<TouchEvent>()
+    }
+
+    pub(crate) fn input(&mut self) -> Vec<Box<dyn Event>> {
+        self.elapsed = Duration::ZERO;
+        match std::mem::take(&mut self.reached) {
+            0 => vec![],
+            _ => vec![Box::new(IdleExited)],
+        }
+    }
+
+    pub(crate) fn advance(&mut self, delta: Duration) -> Vec<Box<dyn Event>> {
+        self.elapsed += delta;
+        let passed = self.thresholds[self.reached..].iter().take_while(|t| **t <= self.elapsed).copied().collect::<Vec<_>>();
+        self.reached += passed.len();
+        passed.into_iter().map(|t| Box::new(IdleEntered(t)) as Box<dyn Event>).collect()
+    }
+
+    /// Whether another threshold is passed within `delta` of the last frame.
+    pub(crate) fn due_within(&self, delta: Duration) -> bool {
+        self.thresholds.get(self.reached).is_some_and(|t| self.elapsed + delta >= *t)
+    }
+}
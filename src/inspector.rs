@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use serde::{Serialize, Deserialize};
+
+use crate::canvas::{self, Color, Instruction, Item, Shape, ShapeType};
+use crate::drawable::{Drawable, RequestTree, SizedTree, Offset, Rect};
+use crate::event::Event;
+use crate::serialize::{EventRegistry, Recorded};
+
+/// A drawable in the tree sent to inspector clients.
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    pub name: String,
+    /// The size request as `(min_width, min_height, max_width, max_height)`.
+    pub request: (f32, f32, f32, f32),
+    /// The area on screen as `(x, y, width, height)`.
+    pub area: Rect,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    fn new(drawable: &dyn Drawable, request: &RequestTree, sized: &SizedTree, offset: Offset) -> Self {
+        let r = request.0;
+        Node {
+            name: drawable.name(),
+            request: (r.min_width(), r.min_height(), r.max_width(), r.max_height()),
+            area: (offset.0, offset.1, sized.0.0, sized.0.1),
+            children: drawable.inspect().into_iter().filter_map(|(branch, child)| match branch {
+                None => Some(Node::new(child, request, sized, offset)),
+                Some(i) => {
+                    let ((o, sized), request) = (sized.1.get(i)?, request.1.get(i)?);
+                    Some(Node::new(child, request, sized, (offset.0 + o.0, offset.1 + o.1)))
+                }
+            }).collect()
+        }
+    }
+
+    fn find(&self, path: &[usize]) -> Option<&Node> {
+        match path.split_first() {
+            None => Some(self),
+            Some((i, rest)) => self.children.get(*i)?.find(rest),
+        }
+    }
+}
+
+/// One item drawn in a frame, as streamed to mirroring clients.
+#[derive(Debug, Clone, Serialize)]
+pub struct Drawn {
+    /// `"shape"`, `"text"` or `"image"`.
+    pub kind: &'static str,
+    pub offset: Offset,
+    /// The shape's or tint's color as `(r, g, b, a)`, if it has one.
+    pub color: Option<(u8, u8, u8, u8)>,
+    /// The text of every span, for text.
+    pub text: Option<String>,
+    /// The whole item debug formatted, for viewers that redraw it exactly.
+    pub item: String,
+}
+
+impl Drawn {
+    fn new(Instruction(area, item): &Instruction) -> Self {
+        let rgba = |c: &Color| (c.0, c.1, c.2, c.3);
+        let (kind, color, text) = match item {
+            Item::Shape(shape) => ("shape", Some(rgba(&shape.color)), None),
+            Item::Text(text) => ("text", text.spans.first().map(|span| rgba(&span.color)), Some(text.spans.iter().map(|span| span.text.as_str()).collect())),
+            Item::Image(image) => ("image", image.color.as_ref().map(rgba), None),
+        };
+        Drawn{kind, offset: area.offset, color, text, item: format!("{item:?}")}
+    }
+}
+
+/// What a client sent, one JSON object per line.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Command {
+    /// Asks for the current tree and event counts.
+    Tree,
+    /// Highlights the node reached by following child indexes down the tree.
+    Select{path: Vec<usize>},
+    Clear,
+    /// Starts or stops streaming every frame drawn to this client.
+    Mirror{enabled: bool},
+    /// Delivers an event to the app as if the host had sent it, such as a [`MouseEvent`](crate::event::MouseEvent) to drive it remotely.
+    Input{event: Recorded},
+}
+
+/// What is sent back, one JSON object per line.
+#[derive(Debug, Serialize)]
+#[serde(tag = "reply", rename_all = "snake_case")]
+enum Reply<'a> {
+    Tree{root: &'a Node, events: &'a HashMap<&'static str, u64>},
+    Selected{node: Option<&'a Node>},
+    Frame{screen: (f32, f32), root: &'a Node, items: &'a [Drawn]},
+    Injected{accepted: bool},
+}
+
+/// A connected client, its commands read a line at a time and its replies buffered until the socket takes them.
+struct Client {
+    reader: BufReader<TcpStream>,
+    /// Replies not yet written, flushed as far as the socket allows every frame so none is cut short.
+    outgoing: Vec<u8>,
+    mirroring: bool,
+}
+
+impl Client {
+    fn new(stream: TcpStream) -> Self {Client{reader: BufReader::new(stream), outgoing: vec![], mirroring: false}}
+
+    fn send(&mut self, json: &[u8]) {
+        self.outgoing.extend_from_slice(json);
+        self.outgoing.push(b'\n');
+    }
+
+    /// Writes as much buffered output as the socket takes without blocking, returning whether the client is still connected.
+    fn flush(&mut self) -> bool {
+        while !self.outgoing.is_empty() {
+            match self.reader.get_mut().write(&self.outgoing) {
+                Ok(0) => return false,
+                Ok(written) => {self.outgoing.drain(..written);},
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Serves the live drawable tree of an [`Instance`](crate::Instance) over a TCP socket, for a separate
+/// devtools client to browse and highlight, or a desktop viewer to mirror and drive an app running on a phone.
+///
+/// Clients send and receive newline separated JSON: `{"command": "tree"}` answers with the tree of drawable
+/// names, size requests and areas along with how many of each event type have been delivered,
+/// `{"command": "select", "path": [0, 2]}` outlines a node on screen and `{"command": "clear"}` removes the outline.
+/// `{"command": "mirror", "enabled": true}` streams each frame's tree and drawn items as it is drawn, skipping frames
+/// while the client is still receiving an earlier one, and `{"command": "input", "event": {"name": "MouseEvent",
+/// "event": {..}}}` injects an event named in the default [`EventRegistry`].
+pub struct Inspector {
+    listener: TcpListener,
+    /// Connections accepted while checking for waiting clients between frames.
+    incoming: RefCell<Vec<TcpStream>>,
+    clients: Vec<Client>,
+    registry: EventRegistry,
+    events: HashMap<&'static str, u64>,
+    selected: Option<Vec<usize>>,
+}
+
+impl Inspector {
+    pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Inspector{listener, incoming: RefCell::default(), clients: vec![], registry: EventRegistry::default(), events: HashMap::new(), selected: None})
+    }
+
+    pub(crate) fn count(&mut self, event: &dyn Event) {*self.events.entry(event.type_name()).or_default() += 1;}
+
+    /// Whether a client has sent a command, is waiting to connect or still has replies to receive, which only
+    /// drawing a frame answers.
+    pub(crate) fn is_waiting(&self) -> bool {
+        self.accept();
+        !self.incoming.borrow().is_empty() || self.clients.iter().any(|client| {
+            !client.outgoing.is_empty() || !client.reader.buffer().is_empty() || client.reader.get_ref().peek(&mut [0]).is_ok()
+        })
+    }
+
+    fn accept(&self) {
+        let mut incoming = self.incoming.borrow_mut();
+        while let Ok((stream, _)) = self.listener.accept() {incoming.push(stream);}
+    }
+
+    /// Answers one command line from `client`, collecting any event it injects.
+    fn answer(&mut self, client: &mut Client, line: &str, root: &Node, injected: &mut Vec<Box<dyn Event>>) {
+        let reply = match serde_json::from_str::<Command>(line) {
+            Ok(Command::Tree) => Reply::Tree{root, events: &self.events},
+            Ok(Command::Select{path}) => {
+                self.selected = Some(path);
+                Reply::Selected{node: self.selected.as_ref().and_then(|path| root.find(path))}
+            },
+            Ok(Command::Clear) => {
+                self.selected = None;
+                Reply::Selected{node: None}
+            },
+            Ok(Command::Mirror{enabled}) => {
+                client.mirroring = enabled;
+                return;
+            },
+            Ok(Command::Input{event}) => {
+                let event = self.registry.deserialize(event);
+                let accepted = event.is_some();
+                injected.extend(event);
+                Reply::Injected{accepted}
+            },
+            Err(_) => return,
+        };
+        if let Ok(json) = serde_json::to_vec(&reply) {client.send(&json);}
+    }
+
+    /// Answers every waiting command against the tree just drawn and streams the frame to mirroring clients,
+    /// returning the outline of the selected node and the events clients injected.
+    pub(crate) fn poll(&mut self, app: &dyn Drawable, request: &RequestTree, sized: &SizedTree, drawn: &[Instruction]) -> (Vec<Instruction>, Vec<Box<dyn Event>>) {
+        self.accept();
+        for stream in self.incoming.take() {
+            if stream.set_nonblocking(true).is_ok() {self.clients.push(Client::new(stream));}
+        }
+
+        let root = Node::new(app, request, sized, (0.0, 0.0));
+        let mut injected = vec![];
+        let mut clients = std::mem::take(&mut self.clients);
+        clients.retain_mut(|client| loop {
+            let mut line = String::new();
+            match client.reader.read_line(&mut line) {
+                Ok(0) => break false,
+                Ok(_) => self.answer(client, &line, &root, &mut injected),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break true,
+                Err(_) => break false,
+            }
+        });
+
+        let mut frame = None;
+        for client in clients.iter_mut().filter(|client| client.mirroring && client.outgoing.is_empty()) {
+            let json = frame.get_or_insert_with(|| {
+                let items = drawn.iter().map(Drawn::new).collect::<Vec<_>>();
+                serde_json::to_vec(&Reply::Frame{screen: sized.0, root: &root, items: &items}).ok()
+            });
+            if let Some(json) = json {client.send(json);}
+        }
+        clients.retain_mut(Client::flush);
+        self.clients = clients;
+
+        let outline = self.selected.as_ref().and_then(|path| root.find(path)).map(|node| {
+            let (x, y, w, h) = node.area;
+            let fill = Shape{shape: ShapeType::Rectangle(0.0, (w, h), 0.0), color: Color(96, 165, 250, 64)};
+            let outline = Shape{shape: ShapeType::Rectangle(1.0, (w, h), 0.0), color: Color(37, 99, 235, 255)};
+            [fill, outline].into_iter().map(|shape| Instruction(canvas::Area{offset: (x, y), bounds: None}, Item::Shape(shape))).collect()
+        }).unwrap_or_default();
+        (outline, injected)
+    }
+}
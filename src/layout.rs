@@ -1,5 +1,11 @@
 use std::sync::{Mutex, Arc};
 
+use crate::i18n::Direction;
+use crate::drawable::SizedTree;
+use crate::display::ScrollAlign;
+
+/// A rectangle in logical pixels, which the display's [scale factor](crate::Context::scale_factor) turns into
+/// physical pixels only when drawing.
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub struct Area {
     pub offset: (f32, f32),
@@ -10,17 +16,17 @@ pub struct Area {
 pub trait Layout: std::fmt::Debug {
 
     /// Given a list of children size requests calculate the size request for the total layout
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest;
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest;
 
     /// Given an allotted size and the list of chlidren size requests (which may respect the size request),
     /// calculate the actual offsets and allotted sizes for its children
-    fn build(&self, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area>;
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area>;
 }
 
 /// Structure used to designate space to a component or drawable.
 ///
 /// A `SizeRequest` specifies the minimum and maximum dimensions that a
-/// component is able to occupy. Layout systems can use this
+/// component is able to occupy, in logical pixels. Layout systems can use this
 /// information to determine how to allocate space during rendering.
 #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
 pub struct SizeRequest {
@@ -28,6 +34,9 @@ pub struct SizeRequest {
     min_height: f32,
     max_width: f32,
     max_height: f32,
+    /// The share of free space a [`Row`] or [`Column`] gives this request, `0.0` unless set by a
+    /// [`Flexible`](crate::drawable::Flexible).
+    weight: f32,
 }
 impl SizeRequest {
     /// Returns the minimum width.
@@ -42,21 +51,27 @@ impl SizeRequest {
     /// Returns the maximum height.
     pub fn max_height(&self) -> f32 { self.max_height }
 
+    /// Returns the flex weight.
+    pub fn weight(&self) -> f32 { self.weight }
+
+    /// Returns the same request with a flex weight, see [`UniformExpand::weighted`].
+    pub fn with_weight(&self, weight: f32) -> SizeRequest { SizeRequest { weight, ..*self } }
+
     /// Creates a new `SizeRequest`, panicking if min > max for either dimension.
     pub fn new(min_width: f32, min_height: f32, max_width: f32, max_height: f32) -> Self {
         if min_width > max_width { panic!("Min Width was Greater Than Max Width"); }
         if min_height > max_height { panic!("Min Height was Greater Than Max Height"); }
-        SizeRequest { min_width, min_height, max_width, max_height }
+        SizeRequest { min_width, min_height, max_width, max_height, weight: 0.0 }
     }
 
     /// Creates a fixed-size `SizeRequest` where min and max are equal.
     pub fn fixed(size: (f32, f32)) -> Self {
-        SizeRequest { min_width: size.0, min_height: size.1, max_width: size.0, max_height: size.1 }
+        SizeRequest { min_width: size.0, min_height: size.1, max_width: size.0, max_height: size.1, weight: 0.0 }
     }
 
     /// Creates a `SizeRequest` that can expand to fill all available space.
     pub fn fill() -> Self {
-        SizeRequest { min_width: 0.0, min_height: 0.0, max_width: f32::MAX, max_height: f32::MAX }
+        SizeRequest { min_width: 0.0, min_height: 0.0, max_width: f32::MAX, max_height: f32::MAX, weight: 0.0 }
     }
 
     /// Clamps a given size into this request's min/max bounds.
@@ -74,17 +89,17 @@ impl SizeRequest {
 
     /// Returns a new request with width increased.
     pub fn add_width(&self, w: f32) -> SizeRequest {
-        SizeRequest::new(self.min_width + w, self.min_height, self.max_width + w, self.max_height)
+        SizeRequest::new(self.min_width + w, self.min_height, self.max_width + w, self.max_height).with_weight(self.weight)
     }
 
     /// Returns a new request with height increased.
     pub fn add_height(&self, h: f32) -> SizeRequest {
-        SizeRequest::new(self.min_width, self.min_height + h, self.max_width, self.max_height + h)
+        SizeRequest::new(self.min_width, self.min_height + h, self.max_width, self.max_height + h).with_weight(self.weight)
     }
 
     /// Returns a new request with height decreased.
     pub fn remove_height(&self, h: f32) -> SizeRequest {
-        SizeRequest::new(self.min_width, self.min_height - h, self.max_width, self.max_height - h)
+        SizeRequest::new(self.min_width, self.min_height - h, self.max_width, self.max_height - h).with_weight(self.weight)
     }
 
     /// Returns the combined maximum of two requests.
@@ -111,12 +126,12 @@ impl SizeRequest {
 #[derive(Debug, Clone, Copy)]
 pub struct DefaultStack;
 impl Layout for DefaultStack {
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest {
-        children.into_iter().reduce(|c, o| c.max(&o)).unwrap()
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        children.iter().copied().reduce(|c, o| c.max(&o)).unwrap()
     }
 
-    fn build(&self, size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
-        children.into_iter().map(|c| Area{offset: (0.0, 0.0), size: c.get(size)}).collect()
+    fn build(&self, size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        children.iter().map(|c| Area{offset: (0.0, 0.0), size: c.get(size)}).collect()
     }
 }
 
@@ -139,6 +154,14 @@ impl Offset {
         }
     }
 
+    /// Like [`Offset::get`] along the x axis, where `Start` is the right edge in a [`Direction::Rtl`] subtree.
+    pub fn horizontal(&self, max_size: f32, item_size: f32) -> f32 {
+        match Direction::current() {
+            Direction::Ltr => self.get(max_size, item_size),
+            Direction::Rtl => max_size - item_size - self.get(max_size, item_size),
+        }
+    }
+
     pub fn size(&self) -> Option<f32> {
         match self {
             Self::Start => Some(0.0),
@@ -257,6 +280,28 @@ impl Padding {
 pub struct UniformExpand;
 
 impl UniformExpand {
+    /// Shares the free space between the sizes with a weight above zero in proportion to their weights, leaving
+    /// the rest at their minimum, or expands every size evenly with [`UniformExpand::get`] when none has a weight.
+    pub fn weighted(sizes: Vec<(f32, f32)>, weights: &[f32], max_size: f32, spacing: f32) -> Vec<f32> {
+        if weights.iter().all(|w| *w <= 0.0) {return UniformExpand::get(sizes, max_size, spacing);}
+        let spacing = sizes.len().saturating_sub(1) as f32 * spacing;
+        let mut result = sizes.iter().map(|s| s.0).collect::<Vec<_>>();
+        let mut free = (max_size - result.iter().sum::<f32>() - spacing).max(0.0);
+        let mut growing = (0..sizes.len()).filter(|i| weights[*i] > 0.0 && sizes[*i].0 < sizes[*i].1).collect::<Vec<_>>();
+        while free > 0.01 && !growing.is_empty() {
+            let total = growing.iter().map(|i| weights[*i]).sum::<f32>();
+            let mut used = 0.0;
+            growing.retain(|i| {
+                let grown = (result[*i] + free * weights[*i] / total).min(sizes[*i].1);
+                used += grown - result[*i];
+                result[*i] = grown;
+                grown < sizes[*i].1
+            });
+            free -= used;
+        }
+        result
+    }
+
     pub fn get(sizes: Vec<(f32, f32)>, max_size: f32, spacing: f32) -> Vec<f32> {
         if sizes.is_empty() {return vec![];}
         let spacing = sizes.len().saturating_sub(1) as f32 * spacing;
@@ -319,15 +364,41 @@ impl UniformExpand {
 ///
 ///```rust
 /// let layout = Row::new(24.0, Offset::Center, Size::Fit, Padding::new(8.0));
+/// let carousel = Row::start(8.0).scrollable();
 ///```
-#[derive(Debug, Default, Clone, PartialEq)]
-pub struct Row(f32, Offset, Size, Padding);
+#[derive(Debug, Default, Clone)]
+pub struct Row(f32, Offset, Size, Padding, Option<Arc<Mutex<f32>>>);
+
+impl PartialEq for Row {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+        && self.1 == other.1
+        && self.2 == other.2
+        && self.3 == other.3
+        && self.4.is_some() == other.4.is_some()
+    }
+}
 
 impl Row {
     pub fn new(spacing: f32, offset: Offset, size: Size, padding: Padding) -> Self {
-        Row(spacing, offset, size, padding)
+        Row(spacing, offset, size, padding, None)
+    }
+
+    /// Lets the row be narrower than its children, scrolling them sideways with [`Row::adjust_scroll`].
+    pub fn scrollable(mut self) -> Self {
+        self.4 = Some(Arc::new(Mutex::new(0.0)));
+        self
     }
 
+    pub fn adjust_scroll(&mut self, delta: f32) {
+        if let Some(s) = &mut self.4 { **s.lock().as_mut().unwrap() += delta; }
+    }
+
+    pub fn set_scroll(&mut self, val: f32) { if let Some(s) = &mut self.4 { **s.lock().as_mut().unwrap() = val; } }
+
+    /// Returns the current scroll offset, or `None` if the row does not scroll.
+    pub fn scroll(&self) -> Option<f32> { self.4.as_ref().map(|s| *s.lock().unwrap()) }
+
     pub fn center(spacing: f32) -> Self {
         Row::new(spacing, Offset::Center, Size::Fit, Padding::default())
     }
@@ -344,26 +415,36 @@ impl Row {
 }
 
 impl Layout for Row {
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest {
-        let (widths, heights): (Vec<_>, Vec<_>) = children.into_iter().map(|i|
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let (widths, heights): (Vec<_>, Vec<_>) = children.iter().map(|i|
             ((i.min_width(), i.max_width()), (i.min_height(), i.max_height()))
         ).unzip();
-        let spacing = self.0 * (widths.len() - 1) as f32;
+        let spacing = self.0 * widths.len().saturating_sub(1) as f32;
         let width = Size::add(widths);
         let height = self.2.get(heights, Size::max);
-        self.3.adjust_request(SizeRequest::new(width.0, height.0, width.1, height.1).add_width(spacing))
+        let min_width = if self.4.is_some() {0.0} else {width.0};
+        self.3.adjust_request(SizeRequest::new(min_width, height.0, width.1, height.1).add_width(spacing))
     }
 
-    fn build(&self, row_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
+    fn build(&self, row_size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        let full_width = row_size.0;
         let row_size = self.3.adjust_size(row_size);
-        let widths = UniformExpand::get(children.iter().map(|i| (i.min_width(), i.max_width())).collect::<Vec<_>>(), row_size.0, self.0);
-        let mut offset = 0.0;
-        children.into_iter().zip(widths).map(|(i, width)| {
+        let weights = children.iter().map(SizeRequest::weight).collect::<Vec<_>>();
+        let widths = UniformExpand::weighted(children.iter().map(|i| (i.min_width(), i.max_width())).collect::<Vec<_>>(), &weights, row_size.0, self.0);
+        let scroll = self.4.as_ref().map(|s| {
+            let content_width = widths.iter().sum::<f32>() + self.0 * widths.len().saturating_sub(1) as f32;
+            let mut v = s.lock().unwrap();
+            *v = v.clamp(0.0, (content_width - row_size.0).max(0.0));
+            *v
+        }).unwrap_or(0.0);
+        let mut offset = -scroll;
+        let areas = children.iter().zip(widths).map(|(i, width)| {
             let size = i.get((width, row_size.1));
             let off = self.3.adjust_offset((offset, self.1.get(row_size.1, size.1)));
             if size.0 > 0.0 {offset += size.0+self.0;}
             Area{offset: off, size}
-        }).collect()
+        }).collect();
+        Direction::current().mirror(areas, full_width)
     }
 }
 
@@ -418,17 +499,31 @@ impl Column {
     }
 
     pub fn set_scroll(&mut self, val: f32) { if let Some(s) = &mut self.4 { **s.lock().as_mut().unwrap() = val; } }
+
+    /// Returns the current scroll offset, or `None` if the column does not scroll.
+    pub fn scroll(&self) -> Option<f32> { self.4.as_ref().map(|s| *s.lock().unwrap()) }
+
+    /// Scrolls the child at `index` into the column as `align` asks, given the [`SizedTree`] of the component the
+    /// column lays out, placing it as [`ScrollView::scroll_to`](crate::display::ScrollView::scroll_to) would.
+    ///
+    /// Scrolling is clamped to the content on the next layout. Put the content in a
+    /// [`ScrollView`](crate::display::ScrollView) instead to animate there and hear when it settles.
+    pub fn scroll_to(&mut self, index: usize, align: ScrollAlign, sized: &SizedTree) {
+        let Some((offset, child)) = sized.1.get(index) else {return};
+        let viewport = self.3.adjust_size(sized.0).1;
+        self.adjust_scroll(align.offset((offset.1 - self.3.1, child.0.1), 0.0, viewport));
+    }
 }
 
 impl Layout for Column {
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
         if children.is_empty() { return SizeRequest::default() };
-        let (widths, heights): (Vec<_>, Vec<_>) = children.into_iter().map(|i|
+        let (widths, heights): (Vec<_>, Vec<_>) = children.iter().map(|i|
             ((i.min_width(), i.max_width()), (i.min_height(), i.max_height()))
         ).unzip();
         let spacing = self.0*(heights.len()-1) as f32;
         let width = self.2.get(widths, Size::max);
-        let height = Size::add(heights.clone());
+        let height = Size::add(heights);
         let size_request = match self.4.is_some() {
             true => SizeRequest::new(0.0, 0.0, width.1, height.1),
             false => SizeRequest::new(width.0, height.0, width.1, height.1)
@@ -436,35 +531,35 @@ impl Layout for Column {
         self.3.adjust_request(size_request.add_height(spacing))
     }
 
-    fn build(&self, col_size: (f32, f32), mut children: Vec<SizeRequest>) -> Vec<Area> {
+    fn build(&self, col_size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
         let col_size = self.3.adjust_size(col_size);
 
-        let spacing = self.0 * children.len().saturating_sub(1) as f32;
-        let ranges: Vec<_> = children.iter().map(|c| (c.min_height(), c.max_height())).collect();
-        let heights = UniformExpand::get(ranges, col_size.1, self.0);
-        let content_height = heights.iter().sum::<f32>() + spacing;
-
-        let mut offset = 0.0; //Offset::Start.get(col_size.1, content_height).max(0.0);
-
-        let max_scroll = (content_height - col_size.1).max(0.0);
+        let weights = children.iter().map(SizeRequest::weight).collect::<Vec<_>>();
+        let heights = UniformExpand::weighted(children.iter().map(|c| (c.min_height(), c.max_height())).collect(), &weights, col_size.1, self.0);
         let scroll = self.4.as_ref().map(|s| {
+            let content_height = heights.iter().sum::<f32>() + self.0 * children.len().saturating_sub(1) as f32;
             let mut v = s.lock().unwrap();
-            *v = v.clamp(0.0, max_scroll);
+            *v = v.clamp(0.0, (content_height - col_size.1).max(0.0));
             *v
         }).unwrap_or(0.0);
 
         let is_end = self.5 == ScrollAnchor::End;
-        if is_end { children.reverse(); }
-
-        let mut areas: Vec<_> = children.into_iter().zip(heights).map(|(child, h)| {
+        let mut offset = 0.0;
+        let mut place = |child: &SizeRequest, h: f32| {
             let size = child.get((col_size.0, h));
             let off_y = { let o = offset; offset += size.1 + self.0; o };
             let n = if is_end {col_size.1 - h + scroll} else {off_y - scroll};
-            Area { offset: self.3.adjust_offset((self.1.get(col_size.0, size.0), n)), size }
-        }).collect();
+            Area { offset: self.3.adjust_offset((self.1.horizontal(col_size.0, size.0), n)), size }
+        };
 
-        if is_end { areas.reverse(); }
-        areas
+        match is_end {
+            true => {
+                let mut areas: Vec<_> = children.iter().rev().zip(heights).map(|(child, h)| place(child, h)).collect();
+                areas.reverse();
+                areas
+            },
+            false => children.iter().zip(heights).map(|(child, h)| place(child, h)).collect()
+        }
     }
 
 }
@@ -505,8 +600,8 @@ impl Stack {
 }
 
 impl Layout for Stack {
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest {
-        let (widths, heights): (Vec<_>, Vec<_>) = children.into_iter().map(|r|
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let (widths, heights): (Vec<_>, Vec<_>) = children.iter().map(|r|
             ((r.min_width(), r.max_width()), (r.min_height(), r.max_height()))
         ).unzip();
         let width = self.2.get(widths, Size::max);
@@ -514,9 +609,9 @@ impl Layout for Stack {
         self.4.adjust_request(SizeRequest::new(width.0, height.0, width.1, height.1))
     }
 
-    fn build(&self, stack_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
+    fn build(&self, stack_size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
         let stack_size = self.4.adjust_size(stack_size);
-        children.into_iter().map(|i| {
+        children.iter().map(|i| {
             let size = i.get(stack_size);
             let offset = (self.0.get(stack_size.0, size.0), self.1.get(stack_size.1, size.1));
             Area{offset: self.4.adjust_offset(offset), size}
@@ -566,7 +661,7 @@ impl PartialEq for Wrap {
 }
 
 impl Layout for Wrap {
-    fn request_size(&self, children: Vec<SizeRequest>) -> SizeRequest {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
         let available_width = *self.5.lock().unwrap();
 
         let left = self.4.1;
@@ -605,7 +700,7 @@ impl Layout for Wrap {
         let mut max_used_w = 0.0_f32;
         let mut has_any = false;
 
-        for child in &children {
+        for child in children {
             let w = child.min_width();
             let h = child.min_height();
 
@@ -639,7 +734,7 @@ impl Layout for Wrap {
         SizeRequest::new(min_width, min_height, max_width, max_height)
     }
 
-    fn build(&self, maximum_size: (f32, f32), children: Vec<SizeRequest>) -> Vec<Area> {
+    fn build(&self, maximum_size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
         let left = self.4.1;
         let right = self.4.2;
         let top = self.4.0;
@@ -740,7 +835,147 @@ impl Layout for Wrap {
             y += line_h + self.1;
         }
 
-        areas
+        Direction::current().mirror(areas, maximum_size.0)
+    }
+}
+
+/// Row-major table layout where every row shares the same column widths.
+///
+/// Children are placed left to right, wrapping to a new row after every `widths.len()` children.
+/// Each row is as tall as its tallest cell. Without any widths every child is given an empty area.
+///
+///```rust
+/// let layout = Table::new(vec![120.0, 80.0, 80.0], 8.0, 4.0);
+///```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Table(pub Vec<f32>, pub f32, pub f32);
+
+impl Table {
+    pub fn new(widths: Vec<f32>, column_spacing: f32, row_spacing: f32) -> Self {
+        Table(widths, column_spacing, row_spacing)
+    }
+
+    pub fn widths(&mut self) -> &mut Vec<f32> {&mut self.0}
+
+    fn row_heights(&self, children: &[SizeRequest]) -> Vec<f32> {
+        children.chunks(self.0.len().max(1)).map(|row| {
+            row.iter().fold(0.0_f32, |h, c| h.max(c.min_height()))
+        }).collect()
+    }
+}
+
+impl Layout for Table {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let heights = self.row_heights(children);
+        let width = self.0.iter().sum::<f32>() + self.1 * self.0.len().saturating_sub(1) as f32;
+        let height = heights.iter().sum::<f32>() + self.2 * heights.len().saturating_sub(1) as f32;
+        SizeRequest::fixed((width, height))
+    }
+
+    fn build(&self, _size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        // Without columns there is nowhere to put the cells, but every child still needs an area.
+        if self.0.is_empty() {return vec![Area::default(); children.len()];}
+        let heights = self.row_heights(children);
+        let mut y = 0.0;
+        children.chunks(self.0.len().max(1)).zip(heights).flat_map(|(row, height)| {
+            let mut x = 0.0;
+            let areas = row.iter().zip(&self.0).map(|(cell, width)| {
+                let area = Area{offset: (x, y), size: cell.get((*width, height))};
+                x += width + self.1;
+                area
+            }).collect::<Vec<_>>();
+            y += height + self.2;
+            areas
+        }).collect()
+    }
+}
+
+/// How wide a column of a [`Grid`] is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    Fixed(f32),
+    /// A share of the width left over after fixed columns and spacing, never narrower than its widest cell.
+    Fraction(f32),
+}
+
+/// Row-major grid layout whose columns line up across every row.
+///
+/// Children are placed cell by cell, left to right, wrapping to a new row after every column.
+/// Each row is as tall as its tallest cell, and each cell is given its column's width.
+///
+///```rust
+/// let layout = Grid::new(3, 8.0, 8.0);
+/// let layout = Grid::tracks(vec![Track::Fixed(48.0), Track::Fraction(1.0), Track::Fraction(2.0)], 8.0, 4.0);
+///```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid(pub Vec<Track>, pub f32, pub f32, pub Padding);
+
+impl Grid {
+    /// Creates a grid of `columns` equally wide columns.
+    pub fn new(columns: usize, column_spacing: f32, row_spacing: f32) -> Self {
+        Grid(vec![Track::Fraction(1.0); columns.max(1)], column_spacing, row_spacing, Padding::default())
+    }
+
+    pub fn tracks(tracks: Vec<Track>, column_spacing: f32, row_spacing: f32) -> Self {
+        Grid(tracks, column_spacing, row_spacing, Padding::default())
+    }
+
+    pub fn padding(&mut self) -> &mut Padding {&mut self.3}
+
+    fn columns(&self) -> usize {self.0.len().max(1)}
+
+    /// The narrowest each column can be, its fixed width or its widest cell.
+    fn min_widths(&self, children: &[SizeRequest]) -> Vec<f32> {
+        (0..self.columns()).map(|column| match self.0.get(column) {
+            Some(Track::Fixed(width)) => *width,
+            _ => children.iter().skip(column).step_by(self.columns()).fold(0.0_f32, |w, c| w.max(c.min_width())),
+        }).collect()
+    }
+
+    fn row_heights(&self, children: &[SizeRequest]) -> Vec<f32> {
+        children.chunks(self.columns()).map(|row| row.iter().fold(0.0_f32, |h, c| h.max(c.min_height()))).collect()
+    }
+
+    fn spacing(&self, count: usize, spacing: f32) -> f32 {spacing * count.saturating_sub(1) as f32}
+}
+
+impl Layout for Grid {
+    fn request_size(&self, children: &[SizeRequest]) -> SizeRequest {
+        let widths = self.min_widths(children);
+        let heights = self.row_heights(children);
+        let width = widths.iter().sum::<f32>() + self.spacing(widths.len(), self.1);
+        let height = heights.iter().sum::<f32>() + self.spacing(heights.len(), self.2);
+        let max_width = match self.0.iter().any(|track| matches!(track, Track::Fraction(_))) {
+            true => f32::MAX,
+            false => width,
+        };
+        self.3.adjust_request(SizeRequest::new(width, height, max_width, height))
+    }
+
+    fn build(&self, grid_size: (f32, f32), children: &[SizeRequest]) -> Vec<Area> {
+        let full_width = grid_size.0;
+        let grid_size = self.3.adjust_size(grid_size);
+        let minimums = self.min_widths(children);
+        let fixed = self.0.iter().zip(&minimums).map(|(track, min)| match track {Track::Fixed(_) => *min, _ => 0.0}).sum::<f32>();
+        let shares = self.0.iter().map(|track| match track {Track::Fraction(f) => *f, _ => 0.0}).sum::<f32>();
+        let left = (grid_size.0 - fixed - self.spacing(minimums.len(), self.1)).max(0.0);
+        let widths = minimums.iter().enumerate().map(|(column, min)| match self.0.get(column) {
+            Some(Track::Fraction(f)) if shares > 0.0 => min.max(left * f / shares),
+            _ => *min,
+        }).collect::<Vec<_>>();
+
+        let mut y = 0.0;
+        let areas = children.chunks(self.columns()).zip(self.row_heights(children)).flat_map(|(row, height)| {
+            let mut x = 0.0;
+            let areas = row.iter().zip(&widths).map(|(cell, width)| {
+                let area = Area{offset: self.3.adjust_offset((x, y)), size: cell.get((*width, height))};
+                x += width + self.1;
+                area
+            }).collect::<Vec<_>>();
+            y += height + self.2;
+            areas
+        }).collect();
+        Direction::current().mirror(areas, full_width)
     }
 }
 
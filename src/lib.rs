@@ -1,18 +1,74 @@
 use std::fmt::Debug;
 use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::hash::{Hash, Hasher};
+use std::cell::RefCell;
+use std::collections::HashSet;
 
 pub use air::{Name, Id};
 pub use air::{Contract, Reactant};
+/// The clock frames are timed with, the system clock natively and `performance.now()` in the browser, where
+/// `std::time::Instant::now` panics.
+pub use web_time::Instant;
 
-use event::{Event, TickEvent};
+use event::{Event, TickEvent, MouseEvent, MouseState, MouseButton, TouchEvent, TouchPhase};
 use drawable::{Drawable, RequestTree, SizedTree};
 use canvas::Instruction;
+use state::State;
+use service::{Service, Services, Routed};
+use accessibility::{AccessNode, Action, Semantics};
+use focus::Focus;
+
+/// Enters a `tracing` span at debug level until the end of the enclosing block, compiled out without the `tracing` feature.
+macro_rules! span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+/// Records a `tracing` event at trace level, compiled out without the `tracing` feature.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
 
 pub mod event;
 pub mod layout;
 pub mod drawable;
 pub mod display;
 pub mod emitters;
+pub mod state;
+pub mod service;
+pub mod bluetooth;
+pub mod animation;
+pub mod theme;
+pub mod ambient;
+pub mod accessibility;
+pub mod focus;
+pub mod i18n;
+pub mod testing;
+pub mod reload;
+pub mod font;
+pub mod text;
+pub mod machine;
+pub mod caret;
+pub mod cursor;
+pub mod idle;
+pub mod power;
+pub mod metrics;
+pub mod portal;
+pub mod touch;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+#[cfg(feature = "serialize")]
+pub mod serialize;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
 
 pub use wgpu_canvas as canvas;
 
@@ -29,46 +85,781 @@ impl<C: Any + Debug + Clone> Camera for C {
 }
 impl Clone for Box<dyn Camera> {fn clone(&self) -> Self {(**self).clone_camera()}}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Facing { Front, Back, External }
+
+/// A camera the device offers, as listed by [`Context::cameras`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CameraInfo {
+    pub name: String,
+    pub facing: Facing,
+    pub has_torch: bool,
+}
+
+/// What the photo picker opened by [`Context::pick_photo`] offers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhotoOptions {
+    pub multiple: bool,
+    /// The most photos that can be picked when `multiple` is set, unlimited if `None`.
+    pub max: Option<usize>,
+    /// Mime types to offer, such as `"image/png"` or `"image/*"`.
+    pub media: Vec<String>,
+}
+
+impl Default for PhotoOptions {
+    fn default() -> Self {PhotoOptions{multiple: false, max: None, media: vec!["image/*".to_string()]}}
+}
+
+/// A local notification scheduled with [`Context::notify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Identifies the notification to [`Context::cancel_notification`] and in [`event::NotificationTapped`].
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    /// When to show the notification, immediately if `None`.
+    pub at: Option<std::time::SystemTime>,
+}
+
+/// A file type offered by [`Context::pick_file`], such as `FileFilter{name: "Images".into(), extensions: vec!["png".into()]}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+/// An opaque, persistable reference to a file the user granted access to, such as a security scoped
+/// bookmark on iOS or a content URI on Android. It stays valid across launches where the platform allows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileHandle(pub String);
+
+/// The on-screen keyboard layout asked for by [`Context::show_keyboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyboardKind { #[default] Text, Numeric, Decimal, Email, Url, Phone }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScreenOrientation {
+    /// Either portrait direction.
+    Portrait,
+    /// Either landscape direction.
+    Landscape,
+    LandscapeLeft,
+    LandscapeRight,
+}
+
+/// The edge or corner of a desktop window a resize started from, see [`Context::start_window_resize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResizeEdge { North, South, East, West, NorthEast, NorthWest, SouthEast, SouthWest }
+
+/// What [`Context::capture_screen`] takes a picture of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum CaptureTarget {
+    /// Only the app's own window, which needs no permission on most platforms.
+    #[default]
+    Window,
+    /// The whole display, behind [`Permission::ScreenCapture`] on platforms that require it.
+    Screen,
+}
+
+/// The state of the app's desktop window, from [`Context::window_state`] or [`event::WindowStateChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowState {
+    pub minimized: bool,
+    pub maximized: bool,
+    /// Covering the whole screen without a border, as a media viewer does.
+    pub fullscreen: bool,
+    /// Kept above other apps' windows, as a picture in picture panel is.
+    pub always_on_top: bool,
+    /// Whether the window has keyboard focus.
+    pub focused: bool,
+}
+
+/// The device's battery and power saving state, from [`Context::get_power_state`] or [`event::PowerChanged`].
+///
+/// Apps should cut back on animation and background work while `low_power` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerState {
+    /// The battery charge from `0.0` to `1.0`, `None` on devices without a battery.
+    pub level: Option<f32>,
+    pub charging: bool,
+    pub low_power: bool,
+}
+
+/// Whether the device can reach the network, from [`Context::get_network_status`] or [`event::NetworkChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum NetworkStatus {
+    Online,
+    Offline,
+    /// Online over a connection the user pays for by usage, such as cellular data.
+    Metered,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Impact { Light, Medium, Heavy }
+
+/// A haptic feedback played by [`Context::trigger_haptic`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Haptic {
+    /// A tap matching a collision of the given weight, such as a control snapping into place.
+    Impact(Impact),
+    /// A tick as a selection changes, such as a picker wheel moving.
+    Selection,
+    Success,
+    Warning,
+    Error,
+    /// Alternating vibrate and pause durations, starting with a vibration. Played as is only on Android,
+    /// elsewhere as a single [`Impact::Medium`].
+    Pattern(Vec<std::time::Duration>),
+}
+
+impl Default for Haptic {fn default() -> Self {Haptic::Impact(Impact::Medium)}}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Permission { Camera, Microphone, Location, Notifications, Photos, ScreenCapture }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PermissionStatus {
+    /// The user hasn't been asked yet.
+    Undetermined,
+    Granted,
+    /// The user declined but may be asked again.
+    Denied,
+    /// The user declined for good, the permission can only be granted from the system settings.
+    PermanentlyDenied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme { #[default] Light, Dark }
+
+/// The user's display and accessibility settings, from [`Context::get_appearance`] or [`event::AppearanceChanged`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Appearance {
+    pub color_scheme: ColorScheme,
+    /// How much larger than normal text should be drawn, `1.0` by default.
+    pub text_scale: f32,
+    pub reduce_motion: bool,
+    pub high_contrast: bool,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {Appearance{color_scheme: ColorScheme::Light, text_scale: 1.0, reduce_motion: false, high_contrast: false}}
+}
+
+/// The user's language and time settings, from [`Context::get_locale`] or [`event::LocaleChanged`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Locale {
+    /// Preferred languages as BCP 47 tags such as `"en-US"`, most preferred first.
+    pub languages: Vec<String>,
+    /// The ISO 3166 region code such as `"US"`, if set.
+    pub region: Option<String>,
+    pub uses_24_hour: bool,
+    /// The IANA timezone name such as `"Europe/Lisbon"`.
+    pub timezone: String,
+    /// The current offset from UTC in seconds.
+    pub utc_offset: i32,
+}
+
+/// A breakpoint on the width of the screen, for adapting one tree between phones, tablets and desktops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum WidthClass {
+    /// Narrower than 600 logical pixels, most phones in portrait.
+    #[default]
+    Compact,
+    /// From 600 to 840 logical pixels, tablets in portrait and phones in landscape.
+    Medium,
+    /// 840 logical pixels and wider.
+    Expanded,
+}
+
+impl WidthClass {
+    pub fn of(width: f32) -> Self {
+        match width {
+            w if w < 600.0 => WidthClass::Compact,
+            w if w < 840.0 => WidthClass::Medium,
+            _ => WidthClass::Expanded,
+        }
+    }
+}
+
+/// The screen the app is drawn to, from [`Context::viewport`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The size in logical pixels.
+    pub size: (f32, f32),
+    pub scale: f32,
+    pub class: WidthClass,
+    /// [`ScreenOrientation::Portrait`] or [`ScreenOrientation::Landscape`], from the screen's proportions.
+    pub orientation: ScreenOrientation,
+}
+
+/// What to do with [`event::CameraFrame`]s that arrive faster than frames are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backpressure {
+    /// Only the newest undelivered frame is kept.
+    #[default]
+    LatestOnly,
+    /// Up to this many undelivered frames are kept, the oldest dropped first.
+    DropOldest(usize),
+}
+
+/// How a camera started with [`Context::start_camera`] should deliver its frames.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameSettings {
+    /// The preferred frame size, or the camera's default if `None`.
+    pub resolution: Option<(u32, u32)>,
+    /// The preferred frames per second, or the camera's default if `None`.
+    pub frame_rate: Option<f32>,
+    pub format: event::PixelFormat,
+    pub backpressure: Backpressure,
+}
+
+/// The platform services behind [`Context`].
+///
+/// Queries answer synchronously, [`Context::request_clipboard`] and [`Context::request_cloud`] wrapping them for
+/// components that would rather receive the answer as an event. [`Handler::pick_photo`] answers only through
+/// [`event::PickedPhoto`], which the host passes to [`Instance::emit`] once the user has chosen. A host whose
+/// platform answers clipboard reads asynchronously implements [`Handler::request_clipboard`] and emits
+/// [`event::ClipboardContents`] itself, and may likewise emit [`event::CloudValue`] for cloud reads.
+///
+/// Only [`Handler::air`] is required. Every other method defaults to doing nothing and answering as a platform
+/// without the feature would, so a host implements just what its platform supports.
 pub trait Handler {
     fn air(&self) -> &air::Context;
-    fn start_camera(&self) -> Box<dyn Camera>;
-    fn pick_photo(&self);
+    fn check_permission(&self, _permission: Permission) -> PermissionStatus {PermissionStatus::Undetermined}
+    fn request_permission(&self, _permission: Permission) {}
+    fn start_camera(&self, _settings: FrameSettings) -> Box<dyn Camera> {Box::new(testing::NoCamera)}
+    fn cameras(&self) -> Vec<CameraInfo> {vec![]}
+    fn select_camera(&self, _facing: Facing) {}
+    fn set_torch(&self, _on: bool) {}
+    fn pick_photo(&self, _options: PhotoOptions) {}
+    fn capture_screen(&self, _target: CaptureTarget) {}
+
+    fn get_safe_area(&self) -> (f32, f32, f32, f32) {(0.0, 0.0, 0.0, 0.0)}
+    fn get_appearance(&self) -> Appearance {Appearance::default()}
+    fn get_locale(&self) -> Locale {
+        Locale{languages: vec!["en-US".to_string()], region: None, uses_24_hour: false, timezone: "UTC".to_string(), utc_offset: 0}
+    }
+    fn get_power_state(&self) -> PowerState {PowerState::default()}
+    fn get_network_status(&self) -> NetworkStatus {NetworkStatus::Online}
+    fn share_social(&self, _data: String) {}
+    fn open_url(&self, _url: String) {}
+
+    /// Returns the file of an installed font family, for [`font::FontSource::System`].
+    fn system_font(&self, _family: String) -> Option<Vec<u8>> {None}
+
+    fn set_clipboard(&self, _data: String) {}
+    fn get_clipboard(&self) -> Option<String> {None}
+    /// Starts reading the clipboard on a platform that can only read it asynchronously, the host emitting an
+    /// [`event::ClipboardContents`] once it has, and returns whether it did. Platforms answering through
+    /// [`Handler::get_clipboard`] leave it returning false.
+    fn request_clipboard(&self) -> bool {false}
+
+    fn trigger_haptic(&self, _haptic: Haptic) {}
+
+    fn show_keyboard(&self, _kind: KeyboardKind) {}
+    fn hide_keyboard(&self) {}
+    /// Tells the platform where the text caret is, in logical pixels, so IME candidate windows open beside it.
+    fn set_caret_rect(&self, _rect: Option<drawable::Rect>) {}
+    /// Shows or hides the system pointer while it is over the app, hidden while a [`cursor::Cursor`] is drawn instead.
+    fn set_cursor_visible(&self, _visible: bool) {}
+
+    fn lock_orientation(&self, _orientation: ScreenOrientation) {}
+    fn unlock_orientation(&self) {}
+
+    /// Moves the window with the pointer until the button is released, for frameless windows. Ignored on mobile.
+    fn drag_window(&self) {}
+    /// Resizes the window from `edge` with the pointer until the button is released. Ignored on mobile.
+    fn resize_window(&self, _edge: ResizeEdge) {}
+    fn minimize_window(&self) {}
+    /// Maximizes the window, or restores it to its previous size when `maximized` is false.
+    fn maximize_window(&self, _maximized: bool) {}
+    fn set_fullscreen(&self, _fullscreen: bool) {}
+    fn set_always_on_top(&self, _on_top: bool) {}
+    fn window_state(&self) -> WindowState {WindowState{focused: true, ..WindowState::default()}}
+    /// Closes the window, quitting the app if it was the last one.
+    fn close_window(&self) {}
+    fn set_window_title(&self, _title: String) {}
+    fn set_window_icon(&self, _icon: image::RgbaImage) {}
+
+    fn notify(&self, _notification: Notification) {}
+    fn cancel_notification(&self, _id: String) {}
+
+    fn pick_file(&self, _filters: Vec<FileFilter>, _multiple: bool) {}
+    fn save_file(&self, _suggested_name: String, _data: Vec<u8>) {}
+    fn read_file(&self, _handle: FileHandle) -> Option<Vec<u8>> {None}
+
+    fn ble_scan(&self, _services: Vec<uuid::Uuid>) {}
+    fn ble_stop_scan(&self) {}
+    fn ble_connect(&self, _device: bluetooth::Device) {}
+    fn ble_disconnect(&self, _device: bluetooth::Device) {}
+    fn ble_read(&self, _characteristic: bluetooth::Characteristic) {}
+    fn ble_write(&self, _characteristic: bluetooth::Characteristic, _data: Vec<u8>) {}
+    fn ble_subscribe(&self, _characteristic: bluetooth::Characteristic, _subscribe: bool) {}
 
-    fn get_safe_area(&self) -> (f32, f32, f32, f32);
-    fn share_social(&self, data: String);
+    fn get_location(&self) {}
+    fn watch_location(&self, _interval: std::time::Duration) {}
+    fn stop_location(&self) {}
 
-    fn set_clipboard(&self, data: String);
-    fn get_clipboard(&self) -> Option<String>;
+    fn set_cloud(&self, _key: String, _value: String) {}
+    fn get_cloud(&self, _key: String) -> Option<String> {None}
+    fn remove_cloud(&self, _key: String) {}
+    fn set_cloud_batch(&self, values: Vec<(String, String)>) {values.into_iter().for_each(|(key, value)| self.set_cloud(key, value))}
+    fn get_cloud_batch(&self, keys: Vec<String>) -> Vec<Option<String>> {keys.into_iter().map(|key| self.get_cloud(key)).collect()}
+    /// Lists every stored key starting with `prefix`.
+    fn list_cloud(&self, _prefix: String) -> Vec<String> {vec![]}
 
-    fn trigger_haptic(&self);
+    fn set_secure(&self, _key: String, _value: String) {}
+    fn get_secure(&self, _key: String) -> Option<String> {None}
+    fn remove_secure(&self, _key: String) {}
+
+    /// Forwards a [`Service`] call to the host, to be answered through [`Instance::respond`].
+    fn call_service(&self, _id: uuid::Uuid, _name: String, _payload: String) {}
+}
+
+type Task = Pin<Box<dyn Future<Output = Box<dyn Event>>>>;
+
+/// The waker tasks are polled with, flagging that [`Instance::draw`] should poll them again.
+#[derive(Debug)]
+struct TaskWaker(std::sync::atomic::AtomicBool);
+
+impl TaskWaker {
+    fn take(&self) -> bool {self.0.swap(false, std::sync::atomic::Ordering::Relaxed)}
+    fn is_woken(&self) -> bool {self.0.load(std::sync::atomic::Ordering::Relaxed)}
+}
+
+impl std::task::Wake for TaskWaker {
+    fn wake(self: std::sync::Arc<Self>) {self.wake_by_ref()}
+    fn wake_by_ref(self: &std::sync::Arc<Self>) {self.0.store(true, std::sync::atomic::Ordering::Relaxed);}
+}
+
+pub struct Context {
+    handler: &'static mut dyn Handler,
+    events: Vec<Box<dyn Event>>,
+    tasks: Vec<Task>,
+    services: Services,
+    backpressure: Option<Backpressure>,
+    keyboard: Option<Option<KeyboardKind>>,
+    /// The one-shot hardware requests already sent to the handler this frame, by name and hashed arguments.
+    sent: RefCell<HashSet<(&'static str, u64)>>,
+    delta: std::time::Duration,
+    now: Instant,
+    scale: f32,
+    screen: (f32, f32),
+    redraw: bool,
+    focus: Focus,
+    pub state: State,
 }
 
-pub struct Context(&'static mut dyn Handler, Vec<Box<dyn Event>>);
 impl Context {
-    fn new(handler: &mut dyn Handler) -> Self {
-        unsafe { Context(
-            std::mem::transmute::<&mut dyn Handler, &'static mut dyn Handler>(handler),
-            Vec::new()
-        )}
+    fn new(handler: &mut dyn Handler, state: State, services: Services) -> Self {
+        Context {
+            handler: unsafe {std::mem::transmute::<&mut dyn Handler, &'static mut dyn Handler>(handler)},
+            events: Vec::new(),
+            tasks: Vec::new(),
+            services,
+            backpressure: None,
+            keyboard: None,
+            sent: RefCell::default(),
+            delta: std::time::Duration::ZERO,
+            now: Instant::now(),
+            scale: 1.0,
+            screen: (0.0, 0.0),
+            redraw: false,
+            focus: Focus::default(),
+            state
+        }
+    }
+
+    pub fn me(&self) -> Name {self.handler.air().me()}
+    pub fn create<C: Contract>(&self, init: C::Init) -> air::Instance<C> {self.handler.air().create::<C>(init)}
+    pub fn list<C: Contract>(&self) -> Vec<air::Instance<C>> {self.handler.air().list::<C>()}
+    pub fn register<C: Contract>(&self) {self.handler.air().register::<C>()}
+
+    /// Returns whether `permission` is granted without prompting, to decide whether to explain why it is needed first.
+    pub fn check_permission(&self, permission: Permission) -> PermissionStatus {self.handler.check_permission(permission)}
+    /// Prompts the user for `permission` if they can still be asked, answered with an [`event::PermissionResult`].
+    pub fn request_permission(&self, permission: Permission) {
+        if self.first("request_permission", permission) {self.handler.request_permission(permission)}
+    }
+
+    pub fn emit<E: Event>(&mut self, event: E) {self.events.push(Box::new(event))}
+
+    /// Returns the time since the previous frame, for advancing [`animation::Animated`] values on [`TickEvent`].
+    pub fn delta(&self) -> std::time::Duration {self.delta}
+
+    /// Returns the time the current frame is drawn at, for measuring double clicks, long presses and timers.
+    ///
+    /// It comes from the [`Instance`] clock rather than the system, so it stays the same for the whole frame and
+    /// moves on exactly one frame at a time under [`testing::Harness`].
+    pub fn now(&self) -> Instant {self.now}
+
+    /// Runs `future` alongside the app, broadcasting its output as an [`event::Task`] carrying the returned id.
+    ///
+    /// Tasks are polled on the UI thread at the start of the frame after they are spawned or woken, so blocking work
+    /// belongs on another thread with the future only awaiting its result.
+    pub fn spawn<T: Clone + Debug + 'static>(&mut self, future: impl Future<Output = T> + 'static) -> uuid::Uuid {
+        let id = uuid::Uuid::new_v4();
+        self.tasks.push(Box::pin(async move {Box::new(event::Task(id, future.await)) as Box<dyn Event>}));
+        id
+    }
+
+    /// Starts the camera, its frames arriving as [`event::CameraFrame`]s for as long as the returned handle lives.
+    pub fn start_camera(&mut self, settings: FrameSettings) -> Box<dyn Camera> {
+        self.backpressure = Some(settings.backpressure);
+        self.handler.start_camera(settings)
+    }
+
+    pub fn cameras(&self) -> Vec<CameraInfo> {self.handler.cameras()}
+    /// Switches the running camera to the first lens facing `facing`, keeping its [`FrameSettings`].
+    pub fn select_camera(&self, facing: Facing) {self.handler.select_camera(facing)}
+    pub fn set_torch(&self, on: bool) {self.handler.set_torch(on)}
+    /// Opens the photo picker, the chosen photos arriving later as an [`event::PickedPhoto`].
+    pub fn pick_photo(&self, options: PhotoOptions) {self.handler.pick_photo(options)}
+    /// Takes a screenshot, such as to attach to a bug report, arriving as an [`event::ScreenCaptured`]. The host
+    /// asks for [`Permission::ScreenCapture`] first where capturing the whole screen needs it.
+    pub fn capture_screen(&self, target: CaptureTarget) {
+        if self.first("capture_screen", target) {self.handler.capture_screen(target)}
+    }
+
+    pub fn get_safe_area(&self) -> (f32, f32, f32, f32) {self.handler.get_safe_area()}
+    pub fn get_appearance(&self) -> Appearance {self.handler.get_appearance()}
+    pub fn get_locale(&self) -> Locale {self.handler.get_locale()}
+    pub fn get_power_state(&self) -> PowerState {self.handler.get_power_state()}
+    pub fn get_network_status(&self) -> NetworkStatus {self.handler.get_network_status()}
+    pub fn share_social(&self, data: String) {
+        if self.first("share_social", &data) {self.handler.share_social(data)}
+    }
+    /// Opens `url` in whatever the platform registers for its scheme, answered with an [`event::UrlOpened`].
+    pub fn open_url(&self, url: String) {
+        if self.first("open_url", &url) {self.handler.open_url(url)}
+    }
+
+    pub fn set_clipboard(&self, data: String) {self.handler.set_clipboard(data);}
+    pub fn get_clipboard(&self) -> Option<String> {self.handler.get_clipboard()}
+    /// Reads the clipboard, delivering its text as an [`event::ClipboardContents`] next frame, or once read where the
+    /// platform only reads it asynchronously.
+    pub fn request_clipboard(&mut self) {
+        if self.handler.request_clipboard() {return;}
+        let data = self.handler.get_clipboard();
+        self.emit(event::ClipboardContents(data))
+    }
+    /// Reads the clipboard, resolving with the next [`event::ClipboardContents`], for components that would rather
+    /// await the text than match the event in `on_event`.
+    pub fn read_clipboard(&mut self) -> service::Answer<event::ClipboardContents> {
+        self.request(Context::request_clipboard, |_: &event::ClipboardContents| true)
+    }
+
+    pub fn trigger_haptic(&self, haptic: Haptic) {
+        if self.first("trigger_haptic", &haptic) {self.handler.trigger_haptic(haptic)}
+    }
+
+    /// Shows the on-screen keyboard at the end of the frame. Within a frame showing wins over hiding,
+    /// so focus moving from one input to another never dismisses the keyboard.
+    pub fn show_keyboard(&mut self, kind: KeyboardKind) {self.keyboard = Some(Some(kind));}
+    pub fn hide_keyboard(&mut self) {self.keyboard.get_or_insert(None);}
+
+    /// Keeps the screen in `orientation` until [`Context::unlock_orientation`], such as while a video plays.
+    pub fn lock_orientation(&self, orientation: ScreenOrientation) {self.handler.lock_orientation(orientation)}
+    pub fn unlock_orientation(&self) {self.handler.unlock_orientation()}
+
+    /// Lets the pointer move the window from a custom titlebar, see [`emitters::WindowDragRegion`].
+    pub fn start_window_drag(&self) {self.handler.drag_window()}
+    pub fn start_window_resize(&self, edge: ResizeEdge) {self.handler.resize_window(edge)}
+
+    pub fn minimize_window(&self) {self.handler.minimize_window()}
+    /// Maximizes the window, or restores it if it already is, for the middle button of a custom titlebar.
+    pub fn toggle_maximize_window(&self) {self.handler.maximize_window(!self.handler.window_state().maximized)}
+    pub fn maximize_window(&self, maximized: bool) {self.handler.maximize_window(maximized)}
+    pub fn set_fullscreen(&self, fullscreen: bool) {self.handler.set_fullscreen(fullscreen)}
+    pub fn set_always_on_top(&self, on_top: bool) {self.handler.set_always_on_top(on_top)}
+    /// Returns the window's current state, each change to which is also broadcast as an [`event::WindowStateChanged`].
+    pub fn window_state(&self) -> WindowState {self.handler.window_state()}
+    pub fn close_window(&self) {self.handler.close_window()}
+    pub fn set_window_title(&self, title: &str) {self.handler.set_window_title(title.to_string())}
+    pub fn set_window_icon(&self, icon: image::RgbaImage) {self.handler.set_window_icon(icon)}
+
+    /// Whether `request` with `args` is the first of its kind this frame. One-shot hardware requests, such as a
+    /// haptic or a permission prompt, made again with the same arguments within a frame reach the handler once.
+    fn first(&self, request: &'static str, args: impl Hash) -> bool {
+        let mut hasher = std::hash::DefaultHasher::new();
+        args.hash(&mut hasher);
+        self.sent.borrow_mut().insert((request, hasher.finish()))
+    }
+
+    fn flush_keyboard(&mut self) {
+        match self.keyboard.take() {
+            Some(Some(kind)) => self.handler.show_keyboard(kind),
+            Some(None) => self.handler.hide_keyboard(),
+            None => {}
+        }
+    }
+
+    /// Schedules a local notification, replacing any pending one with the same id.
+    /// Opening the app from it emits [`event::NotificationTapped`].
+    pub fn notify(&self, notification: Notification) {self.handler.notify(notification)}
+    pub fn cancel_notification(&self, id: String) {self.handler.cancel_notification(id)}
+
+    /// Opens the platform's file picker, the chosen files arriving as an [`event::PickedFiles`].
+    pub fn pick_file(&self, filters: Vec<FileFilter>, multiple: bool) {self.handler.pick_file(filters, multiple)}
+    /// Asks the user where to save `data`, answered with an [`event::FileSaved`].
+    pub fn save_file(&self, suggested_name: String, data: Vec<u8>) {self.handler.save_file(suggested_name, data)}
+    /// Reads a previously picked or saved file again, `None` if access was revoked or the file is gone.
+    pub fn read_file(&self, handle: FileHandle) -> Option<Vec<u8>> {self.handler.read_file(handle)}
+
+    /// Scans for Bluetooth LE peripherals offering any of `services`, or all of them if empty, until
+    /// [`Context::ble_stop_scan`]. Each one seen is reported with an [`event::Bluetooth::Advertised`].
+    pub fn ble_scan(&self, services: Vec<uuid::Uuid>) {self.handler.ble_scan(services)}
+    pub fn ble_stop_scan(&self) {self.handler.ble_stop_scan()}
+    /// Connects to `device`, answered with [`event::Bluetooth::Connected`] or [`event::Bluetooth::Failed`].
+    pub fn ble_connect(&self, device: bluetooth::Device) {self.handler.ble_connect(device)}
+    pub fn ble_disconnect(&self, device: bluetooth::Device) {self.handler.ble_disconnect(device)}
+    /// Reads `characteristic`, answered with an [`event::Bluetooth::Value`].
+    pub fn ble_read(&self, characteristic: bluetooth::Characteristic) {
+        if self.first("ble_read", &characteristic) {self.handler.ble_read(characteristic)}
+    }
+    pub fn ble_write(&self, characteristic: bluetooth::Characteristic, data: Vec<u8>) {self.handler.ble_write(characteristic, data)}
+    /// Starts or stops receiving an [`event::Bluetooth::Value`] whenever `characteristic` notifies a change.
+    pub fn ble_subscribe(&self, characteristic: bluetooth::Characteristic, subscribe: bool) {self.handler.ble_subscribe(characteristic, subscribe)}
+
+    /// Requests the device's position once, answered with an [`event::LocationUpdate`].
+    pub fn get_location(&self) {
+        if self.first("get_location", ()) {self.handler.get_location()}
+    }
+    /// Requests an [`event::LocationUpdate`] about every `interval` until [`Context::stop_location`].
+    pub fn watch_location(&self, interval: std::time::Duration) {self.handler.watch_location(interval)}
+    pub fn stop_location(&self) {self.handler.stop_location()}
+
+    pub fn set_cloud(&self, key: String, value: String) {self.handler.set_cloud(key, value)}
+    pub fn get_cloud(&self, key: String) -> Option<String> {self.handler.get_cloud(key)}
+    pub fn remove_cloud(&self, key: String) {self.handler.remove_cloud(key)}
+    /// Reads `key` from cloud storage, delivering the result as an [`event::CloudValue`] next frame.
+    pub fn request_cloud(&mut self, key: String) {
+        let value = self.handler.get_cloud(key.clone());
+        self.emit(event::CloudValue(key, value))
+    }
+    /// Reads `key` from cloud storage, resolving with the [`event::CloudValue`] for that key.
+    pub fn read_cloud(&mut self, key: String) -> service::Answer<event::CloudValue> {
+        let matches = key.clone();
+        self.request(|ctx| ctx.request_cloud(key), move |value: &event::CloudValue| value.0 == matches)
+    }
+    pub fn set_cloud_batch(&self, values: Vec<(String, String)>) {self.handler.set_cloud_batch(values)}
+    pub fn get_cloud_batch(&self, keys: Vec<String>) -> Vec<Option<String>> {self.handler.get_cloud_batch(keys)}
+    pub fn list_cloud(&self, prefix: String) -> Vec<String> {self.handler.list_cloud(prefix)}
+
+    /// Stores `value` in the platform's secure storage, the Keychain on iOS and macOS, the Keystore on Android
+    /// and an encrypted file elsewhere. Unlike cloud storage it never leaves the device, so it suits tokens and keys.
+    pub fn set_secure(&self, key: String, value: String) {self.handler.set_secure(key, value)}
+    pub fn get_secure(&self, key: String) -> Option<String> {self.handler.get_secure(key)}
+    pub fn remove_secure(&self, key: String) {self.handler.remove_secure(key)}
+
+    /// Returns cloud storage scoped to `namespace`, so separate features can't overwrite each other's keys.
+    pub fn cloud(&self, namespace: &str) -> Cloud<'_> {Cloud{handler: &*self.handler, prefix: format!("{namespace}/")}}
+
+    /// Answers calls to the [`Service`] `S` in process with `f` instead of forwarding them to the host.
+    pub fn register_service<S: Service>(&mut self, f: impl Fn(S::Request) -> S::Response + 'static) {
+        self.services.register::<S>(f)
+    }
+
+    /// Calls the [`Service`] `S`, returning the id its [`event::ServiceResponse`] will carry.
+    pub fn call<S: Service>(&mut self, request: S::Request) -> uuid::Uuid {
+        let id = uuid::Uuid::new_v4();
+        trace!(service = S::NAME, %id, "call");
+        match self.services.call::<S>(id, request) {
+            Routed::Remote(payload) => self.handler.call_service(id, S::NAME.to_string(), payload),
+            Routed::Local(response) => self.events.push(response),
+        }
+        id
+    }
+
+    /// Starts a query with `start` and returns a future resolving with the first `E` event for which `matches` holds,
+    /// sparing components from matching responses to their requests by hand.
+    ///
+    ///```rust
+    /// let answer = ctx.request(|ctx| ctx.get_location(), |_: &event::LocationUpdate| true);
+    /// ctx.spawn(async move {answer.await.accuracy});
+    ///```
+    pub fn request<E: Event + Clone>(&mut self, start: impl FnOnce(&mut Context), matches: impl Fn(&E) -> bool + 'static) -> service::Answer<E> {
+        let answer = self.services.wait(matches);
+        start(self);
+        answer
+    }
+
+    /// Persists the [`State`] value of type `T` under `name`, restoring any previously saved value now.
+    ///
+    /// Call this while building the app so saved values are in place before the first frame.
+    pub fn persist<T: serde::Serialize + serde::de::DeserializeOwned + 'static>(&mut self, name: &'static str) {
+        let saved = self.handler.get_cloud(name.to_string());
+        self.state.persist::<T>(name, saved);
+    }
+
+    /// Persists the [`State`] value stored under `key`, restoring any previously saved value now.
+    pub fn persist_key<T: serde::Serialize + serde::de::DeserializeOwned + 'static>(&mut self, key: &state::Key<T>) {
+        let saved = self.handler.get_cloud(key.name().to_string());
+        self.state.persist_key(key, saved);
+    }
+
+    /// Returns the current [`theme::Theme`], [`theme::Theme::light`] until one is set.
+    pub fn theme(&self) -> theme::Theme {self.state.get::<theme::Theme>().cloned().unwrap_or_default()}
+
+    /// Swaps the [`theme::Theme`], broadcasting a [`event::StateChanged<theme::Theme>`] so [`theme::Themed`] drawables restyle.
+    pub fn set_theme(&mut self, theme: theme::Theme) {
+        self.state.watch::<theme::Theme>();
+        self.state.set(theme);
+    }
+
+    /// Replaces the builder of every [`reload::Reloadable`] with `id`, rebuilding them on the next frame.
+    pub fn reload(&mut self, id: &str, builder: impl Fn(&mut Context) -> Box<dyn Drawable> + 'static) {
+        self.state.get_or_default::<reload::Registry>().swap(id, std::rc::Rc::new(builder));
+    }
+
+    /// Returns how many physical pixels make up one logical pixel on the current display.
+    ///
+    /// Layout, [`layout::Area`]s and events are all in logical pixels, so this only matters for rasterizing
+    /// images and icons at `size * scale_factor` to keep them sharp on high density displays.
+    pub fn scale_factor(&self) -> f32 {self.scale}
+
+    /// Returns the size, density and width class of the screen, for media query style decisions.
+    /// Wrap a subtree in [`display::Responsive`] to rebuild it when the width class changes.
+    pub fn viewport(&self) -> Viewport {
+        Viewport {
+            size: self.screen,
+            scale: self.scale,
+            class: WidthClass::of(self.screen.0),
+            orientation: match self.screen.0 > self.screen.1 {
+                true => ScreenOrientation::Landscape,
+                false => ScreenOrientation::Portrait,
+            },
+        }
+    }
+
+    /// Registers a font under `name`, adding it to the end of the fallback chain, and returns whether its bytes were
+    /// found and parsed.
+    pub fn register_font(&mut self, name: &str, source: font::FontSource, coverage: font::Coverage) -> bool {
+        let bytes = match source {
+            font::FontSource::Bytes(bytes) => Some(bytes),
+            font::FontSource::System(family) => self.handler.system_font(family),
+        };
+        bytes.and_then(|bytes| {
+            let parsed = canvas::Font::from_bytes(&bytes).ok()?;
+            let font = font::FontData{name: name.to_string(), bytes: std::sync::Arc::new(bytes), font: std::sync::Arc::new(parsed), coverage};
+            self.state.get_or_default::<font::Fonts>().insert(font);
+            self.redraw = true;
+            Some(())
+        }).is_some()
+    }
+
+    /// Sets the order registered fonts are tried in, such as `["Inter", "Noto Sans CJK", "Noto Color Emoji"]`.
+    pub fn set_font_fallback(&mut self, chain: Vec<String>) {
+        self.state.get_or_default::<font::Fonts>().set_fallback(chain);
+        self.redraw = true;
+    }
+
+    /// Returns the registered fonts, for splitting text into runs with [`font::Fonts::runs`].
+    pub fn fonts(&self) -> font::Fonts {self.state.get::<font::Fonts>().cloned().unwrap_or_default()}
+
+    /// Asks for another frame even if no input arrives, for components that are still animating.
+    pub fn request_frame(&mut self) {self.redraw = true;}
+
+    /// Takes focus away from whichever [`focus::Focusable`] has it.
+    pub fn clear_focus(&mut self) {self.focus.set(None, false)}
+
+    /// Gives the shared [`caret::Caret`] to `owner` and restarts its blink, for a text field gaining focus or editing.
+    pub fn move_caret(&mut self, owner: uuid::Uuid) {
+        self.state.get_or_default::<caret::Caret>().claim(owner);
+        self.redraw = true;
     }
 
-    pub fn me(&self) -> Name {self.0.air().me()}
-    pub fn create<C: Contract>(&self, init: C::Init) -> air::Instance<C> {self.0.air().create::<C>(init)}
-    pub fn list<C: Contract>(&self) -> Vec<air::Instance<C>> {self.0.air().list::<C>()}
-    pub fn register<C: Contract>(&self) {self.0.air().register::<C>()}
+    /// Hides the system pointer and draws `cursor` in its place, with `hotspot` being the point of it that sits on the pointer.
+    pub fn set_cursor(&mut self, cursor: impl Drawable, hotspot: drawable::Offset) {
+        self.state.get_or_default::<cursor::Cursor>().set(Some(Box::new(cursor)), hotspot);
+        self.redraw = true;
+    }
 
-    pub fn emit<E: Event>(&mut self, event: E) {self.1.push(Box::new(event))}
+    /// Brings the system pointer back in place of the cursor from [`Context::set_cursor`].
+    pub fn reset_cursor(&mut self) {
+        self.state.get_or_default::<cursor::Cursor>().set(None, (0.0, 0.0));
+        self.redraw = true;
+    }
 
-    pub fn start_camera(&self) -> Box<dyn Camera> {self.0.start_camera()}
-    pub fn pick_photo(&self) {self.0.pick_photo()}
+    /// Returns the counters of recent frames, such as the time spent in each phase and how many drawables were measured.
+    pub fn metrics(&self) -> metrics::Metrics {self.state.get::<metrics::Metrics>().cloned().unwrap_or_default()}
 
-    pub fn get_safe_area(&self) -> (f32, f32, f32, f32) {self.0.get_safe_area()}
-    pub fn share_social(&self, data: String) {self.0.share_social(data)}
+    /// Turns low power mode on or off regardless of the battery and visibility, or back to automatic with `None`.
+    pub fn set_low_power(&mut self, low_power: Option<bool>) {
+        self.state.get_or_default::<power::LowPower>().force(low_power);
+        self.redraw = true;
+    }
 
-    pub fn set_clipboard(&self, data: String) {self.0.set_clipboard(data);}
-    pub fn get_clipboard(&self) -> Option<String> {self.0.get_clipboard()}
+    /// Returns whether the app is in low power mode, see [`power::is_low_power`].
+    pub fn is_low_power(&self) -> bool {self.state.get::<power::LowPower>().is_some_and(|power| power.is_active())}
 
-    pub fn trigger_haptic(&self) {self.0.trigger_haptic()}
+    /// Sets how long without input announces an [`event::IdleEntered`], once for each threshold passed.
+    pub fn set_idle_thresholds(&mut self, thresholds: Vec<std::time::Duration>) {
+        self.state.get_or_default::<idle::Idle>().set_thresholds(thresholds)
+    }
+
+    /// Returns how long it has been since the last pointer or keyboard input.
+    pub fn idle_time(&self) -> std::time::Duration {self.state.get::<idle::Idle>().map(|idle| idle.elapsed()).unwrap_or_default()}
+
+    /// Hides the caret if `owner` still has it, for a text field losing focus.
+    pub fn release_caret(&mut self, owner: uuid::Uuid) {self.state.get_or_default::<caret::Caret>().release(owner)}
+
+    /// Reverts the most recent tracked [`State`] change, see [`State::undo`].
+    pub fn undo(&mut self) -> bool {self.state.undo()}
+
+    /// Reapplies the most recently undone [`State`] change, see [`State::redo`].
+    pub fn redo(&mut self) -> bool {self.state.redo()}
+
+    fn save(&self, values: Vec<(&'static str, Option<String>)>) {
+        values.into_iter().for_each(|(name, value)| match value {
+            Some(value) => self.handler.set_cloud(name.to_string(), value),
+            None => self.handler.remove_cloud(name.to_string())
+        });
+    }
+}
+
+/// Cloud storage under a namespace, from [`Context::cloud`]. Keys are stored as `namespace/key`.
+pub struct Cloud<'a> {
+    handler: &'a dyn Handler,
+    prefix: String,
+}
+
+impl Cloud<'_> {
+    pub fn set(&self, key: &str, value: String) {self.handler.set_cloud(self.key(key), value)}
+    pub fn get(&self, key: &str) -> Option<String> {self.handler.get_cloud(self.key(key))}
+    pub fn remove(&self, key: &str) {self.handler.remove_cloud(self.key(key))}
+
+    pub fn set_batch(&self, values: Vec<(String, String)>) {
+        self.handler.set_cloud_batch(values.into_iter().map(|(key, value)| (self.key(&key), value)).collect())
+    }
+
+    pub fn get_batch(&self, keys: &[&str]) -> Vec<Option<String>> {
+        self.handler.get_cloud_batch(keys.iter().map(|key| self.key(key)).collect())
+    }
+
+    /// Lists the keys in this namespace starting with `prefix`, without the namespace.
+    pub fn list(&self, prefix: &str) -> Vec<String> {
+        self.handler.list_cloud(self.key(prefix)).into_iter()
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string))
+            .collect()
+    }
+
+    fn key(&self, key: &str) -> String {format!("{}{key}", self.prefix)}
 }
 
 pub struct Instance {
@@ -76,13 +867,33 @@ pub struct Instance {
     screen: (f32, f32),
     request: RequestTree,
     size: SizedTree,
-    events: Vec<Box<dyn Event>>
+    events: Vec<Box<dyn Event>>,
+    tasks: Vec<Task>,
+    waker: std::sync::Arc<TaskWaker>,
+    services: Services,
+    backpressure: Backpressure,
+    limit: Option<usize>,
+    last_frame: Option<Instant>,
+    /// The time frames are drawn at in place of the system clock, set by [`Instance::set_clock`].
+    clock: Option<Instant>,
+    scale: f32,
+    redraw: bool,
+    lazy_layout: bool,
+    #[cfg(feature = "inspector")]
+    inspector: Option<inspector::Inspector>,
+    focus: Focus,
+    state: State,
 }
 
 impl Instance {
     pub fn new<D: Drawable>(builder: impl FnOnce(&mut Context) -> D, handler: &mut dyn Handler, screen: (f32, f32)) -> Self {
-        let mut context = Context::new(handler);
+        let mut context = Context::new(handler, State::default(), Services::default());
+        context.screen = screen;
         let app = builder(&mut context);
+        context.flush_keyboard();
+        let low_power = context.handler.get_power_state().low_power;
+        context.state.get_or_default::<power::LowPower>().set_battery(low_power);
+        font::start_frame(context.state.get::<font::Fonts>());
         let size_request = app.request_size();
         let sized_app = app.build(screen, &size_request);
 
@@ -91,35 +902,319 @@ impl Instance {
             screen,
             request: size_request,
             size: sized_app,
-            events: context.1
+            events: context.events,
+            tasks: context.tasks,
+            waker: std::sync::Arc::new(TaskWaker(true.into())),
+            services: context.services,
+            backpressure: context.backpressure.unwrap_or_default(),
+            limit: None,
+            last_frame: None,
+            clock: None,
+            scale: 1.0,
+            redraw: true,
+            lazy_layout: false,
+            #[cfg(feature = "inspector")]
+            inspector: None,
+            focus: context.focus,
+            state: context.state,
         }
     }
 
+    /// Lays the app out for a screen of `screen` logical pixels.
     pub fn resize(&mut self, screen: (f32, f32)) {
+        self.redraw = true;
         self.screen = screen;
-        self.size = self.app.build(self.screen, &self.request);
+        portal::start_layout(screen);
+        std::mem::replace(&mut self.size, self.app.build(self.screen, &self.request)).recycle();
+    }
+
+    /// Sets the display's physical pixels per logical pixel, for when the window opens or moves to another display.
+    ///
+    /// Everything up to [`Instance::draw`] works in logical pixels: hosts divide pointer positions by the factor
+    /// before [`Instance::emit`], and the canvas multiplies the drawn instructions by it once when rendering.
+    pub fn set_scale_factor(&mut self, scale: f32) {
+        self.redraw |= self.scale != scale;
+        self.scale = scale;
     }
 
-    pub fn emit<E: Event>(&mut self, event: E) {self.events.push(Box::new(event));}
+    pub fn scale_factor(&self) -> f32 {self.scale}
+
+    /// Converts a position in physical pixels, as reported by the platform, to the logical pixels events use.
+    pub fn to_logical(&self, physical: (f32, f32)) -> (f32, f32) {(physical.0 / self.scale, physical.1 / self.scale)}
+
+    /// Returns the screen size in physical pixels, for sizing the surface drawn to.
+    pub fn physical_size(&self) -> (f32, f32) {(self.screen.0 * self.scale, self.screen.1 * self.scale)}
+
+    /// Queues an event for the next frame. A scroll at the same position, or a pointer or finger move directly
+    /// following another move of the same pointer or finger, is merged into it, so a busy input source can't
+    /// flood the queue.
+    pub fn emit<E: Event>(&mut self, event: E) {
+        let event: Box<dyn Event> = Box::new(event);
+        if let (Some(last), Some(next)) = (
+            self.events.last_mut().and_then(|e| e.downcast_mut::<MouseEvent>()),
+            event.downcast_ref::<MouseEvent>()
+        ) {
+            match (&mut last.state, next.state) {
+                (MouseState::Scroll(x, y), MouseState::Scroll(dx, dy)) if last.position == next.position => {
+                    *x += dx;
+                    *y += dy;
+                    return;
+                },
+                (MouseState::Moved, MouseState::Moved) => {
+                    last.position = next.position;
+                    return;
+                },
+                _ => {}
+            }
+        }
+        if let (Some(last), Some(next)) = (
+            self.events.last_mut().and_then(|e| e.downcast_mut::<TouchEvent>()),
+            event.downcast_ref::<TouchEvent>()
+        ) && last.id == next.id && last.phase == TouchPhase::Moved && next.phase == TouchPhase::Moved {
+            last.position = next.position;
+            return;
+        }
+        self.events.push(event);
+        if let Some(limit) = self.limit && self.events.len() > limit {
+            self.events.drain(..self.events.len() - limit);
+        }
+    }
+
+    /// Bounds the queue of undelivered events, dropping the oldest once more than `limit` are waiting.
+    pub fn set_event_limit(&mut self, limit: Option<usize>) {self.limit = limit;}
+
+    /// Returns whether [`Instance::draw`] has anything to do: events are waiting, a task was spawned or woken, an
+    /// inspector client is waiting for an answer, a component called [`Context::request_frame`] or the last frame
+    /// handled input, which may have started an animation.
+    /// Hosts skip drawing while this is false so idle screens cost nothing. In low power mode frames only
+    /// animations asked for come at most every [`power::LOW_POWER_FRAME`].
+    pub fn needs_frame(&self) -> bool {
+        let since = self.last_frame.map(|last| self.clock() - last).unwrap_or_default();
+        let blink = self.state.get::<caret::Caret>().is_some_and(|caret| caret.flips_within(since));
+        let idle = self.state.get::<idle::Idle>().is_some_and(|idle| idle.due_within(since));
+        let throttled = self.state.get::<power::LowPower>().is_some_and(|power| power.is_active()) && since < power::LOW_POWER_FRAME;
+        #[cfg(feature = "inspector")]
+        if self.inspector.as_ref().is_some_and(inspector::Inspector::is_waiting) {return true;}
+        !self.events.is_empty() || self.waker.is_woken() || idle || ((self.redraw || blink) && !throttled)
+    }
+
+    /// Measures and lays the app out only on frames where input, a state change or an animation could have
+    /// changed it, instead of on every frame.
+    ///
+    /// Off by default, since turning it on changes what apps must do: a drawable that changes itself on
+    /// [`TickEvent`], such as a label updated from a contract listener, has to call [`Context::request_frame`]
+    /// or it keeps its old size and place until something else redraws.
+    pub fn set_lazy_layout(&mut self, lazy: bool) {self.lazy_layout = lazy;}
+
+    /// Draws frames at `now` in place of the system clock, or back on the system clock with `None`, see [`Context::now`].
+    pub fn set_clock(&mut self, now: Option<Instant>) {self.clock = now;}
 
+    fn clock(&self) -> Instant {self.clock.unwrap_or_else(Instant::now)}
+
+    /// Returns the counters of recent frames, see [`Context::metrics`].
+    pub fn metrics(&self) -> metrics::Metrics {self.state.get::<metrics::Metrics>().cloned().unwrap_or_default()}
+
+    /// Tells the app whether it is on screen, for hosts to call as it moves to and from the background.
+    /// An app that isn't visible runs in low power mode, see [`power::LowPower`].
+    pub fn set_visible(&mut self, visible: bool) {
+        self.state.get_or_default::<power::LowPower>().set_hidden(!visible);
+        self.redraw = true;
+    }
+
+    /// Queues a camera frame, dropping undelivered frames as the camera's [`Backpressure`] asks.
+    pub fn frame(&mut self, frame: event::CameraFrame) {
+        let keep = match self.backpressure {
+            Backpressure::LatestOnly => 0,
+            Backpressure::DropOldest(count) => count.saturating_sub(1),
+        };
+        let mut queued = self.events.iter().filter(|e| e.is::<event::CameraFrame>()).count();
+        self.events.retain(|e| !(e.is::<event::CameraFrame>() && queued > keep && {queued -= 1; true}));
+        self.events.push(Box::new(frame));
+    }
+
+    /// Delivers the host's answer to the [`Service`] call `id`, either the response payload or an error message.
+    pub fn respond(&mut self, id: uuid::Uuid, result: Result<String, String>) {
+        if let Some(event) = self.services.respond(id, result) {self.events.push(event);}
+    }
+
+    /// Returns the accessibility tree of the last built frame, for the host to hand to the platform's screen reader.
+    pub fn accessibility(&self) -> AccessNode {
+        AccessNode{
+            semantics: Semantics::default(),
+            bounds: (0.0, 0.0, self.screen.0, self.screen.1),
+            children: self.app.accessibility(&self.size, (0.0, 0.0))
+        }
+    }
+
+    /// Performs an assistive technology's `action` on the node at `bounds`, as the pointer input it stands for.
+    /// Focusing moves focus to the [`focus::Focusable`] there without pressing it.
+    pub fn perform(&mut self, bounds: drawable::Rect, action: Action) {
+        let position = Some((bounds.0 + bounds.2 / 2.0, bounds.1 + bounds.3 / 2.0));
+        match action {
+            Action::Focus => self.emit(focus::FocusAt{position}),
+            Action::Press => {
+                self.emit(MouseEvent{position, state: MouseState::Pressed(MouseButton::Left)});
+                self.emit(MouseEvent{position, state: MouseState::Released(MouseButton::Left)});
+            },
+            Action::ScrollForward => self.emit(MouseEvent{position, state: MouseState::Scroll(0.0, bounds.3 * 0.8)}),
+            Action::ScrollBackward => self.emit(MouseEvent{position, state: MouseState::Scroll(0.0, -bounds.3 * 0.8)}),
+        }
+    }
+
+    /// Swaps in a new builder for every [`reload::Reloadable`] with `id`, for a host reloading code while the app runs.
+    pub fn reload(&mut self, id: &str, builder: impl Fn(&mut Context) -> Box<dyn Drawable> + 'static) {
+        self.state.get_or_default::<reload::Registry>().swap(id, std::rc::Rc::new(builder));
+    }
+
+    /// Serves the drawable tree to devtools clients connecting to `address`, and mirrors frames to and takes input
+    /// from remote viewers, see [`inspector::Inspector`].
+    #[cfg(feature = "inspector")]
+    pub fn inspect(&mut self, address: impl std::net::ToSocketAddrs) -> std::io::Result<()> {
+        self.inspector = Some(inspector::Inspector::bind(address)?);
+        Ok(())
+    }
+
+    /// Saves every persisted [`State`] value, for when the app is suspended or about to exit.
+    pub fn save(&mut self, handler: &mut dyn Handler) {
+        let mut context = Context::new(handler, std::mem::take(&mut self.state), std::mem::take(&mut self.services));
+        let values = context.state.serialize_all();
+        context.save(values);
+        self.services = context.services;
+        self.state = context.state;
+    }
+
+    /// Runs a frame and returns what to draw. The app is measured and laid out again every frame, or with
+    /// [`Instance::set_lazy_layout`] only when input, a state change or an animation could have changed it.
     pub fn draw(&mut self, handler: &mut dyn Handler) -> Vec<Instruction> {
-        let mut context = Context::new(handler);
-        self.app.event(&mut context, &self.size, Box::new(TickEvent));
+        if self.waker.take() {
+            let waker = std::task::Waker::from(self.waker.clone());
+            let mut waker = std::task::Context::from_waker(&waker);
+            self.tasks.retain_mut(|task| match task.as_mut().poll(&mut waker) {
+                Poll::Ready(event) => {self.events.push(event); false},
+                Poll::Pending => true
+            });
+        }
+        let now = self.clock();
+        let delta = self.last_frame.map(|last| now - last).unwrap_or_default();
+        self.last_frame = Some(now);
+        // A frame that ends an animation stops asking for more, so the frame after a redraw is laid out too.
+        let stale = self.redraw;
+        span!("frame", ?delta);
+        self.state.get_or_default::<caret::Caret>().advance(delta);
+        let input = self.events.iter().any(|event| idle::Idle::is_input(&**event));
+        let idle = self.state.get_or_default::<idle::Idle>();
+        let idle = if input {idle.input()} else {idle.advance(delta)};
+        self.events.extend(idle);
+        let power = self.state.get_or_default::<power::LowPower>();
+        self.events.iter().for_each(|event| power.observe(&**event));
+        self.events.extend(power.update());
+        let mut frame = metrics::FrameMetrics{interval: delta, ..Default::default()};
+        let mut context = Context::new(handler, std::mem::take(&mut self.state), std::mem::take(&mut self.services));
+        context.delta = delta;
+        context.now = now;
+        context.scale = self.scale;
+        context.screen = self.screen;
+        context.focus = std::mem::take(&mut self.focus);
+        context.focus.start_frame();
+        context.state.get_or_default::<portal::Modals>().clear();
+        {
+            span!("tick");
+            let started = Instant::now();
+            self.app.event(&mut context, &self.size, Box::new(TickEvent));
+            frame.tick = started.elapsed();
+        }
+        if context.focus.end_tick() {context.redraw = true;}
+        context.flush_keyboard();
         let mut events = self.events.drain(..).rev().collect::<Vec<_>>();
-        events.extend(context.1);
-        let mut context = Context::new(handler);
-        for event in events {
-            if let Some(event) = event
-                .pass(&mut context, &[prism::layout::Area{offset: (0.0, 0.0), size: self.size.0}])
-                .remove(0)
-            {
-                self.app.event(&mut context, &self.size, event);
+        events.extend(context.events);
+        events.extend(context.state.take_changes());
+        if !context.tasks.is_empty() {std::task::Wake::wake_by_ref(&self.waker);}
+        self.tasks.extend(context.tasks);
+        self.backpressure = context.backpressure.unwrap_or(self.backpressure);
+        let (focus, redraw) = (context.focus, context.redraw || !events.is_empty());
+        let sent = context.sent;
+        let mut context = Context::new(handler, context.state, context.services);
+        context.sent = sent;
+        context.delta = delta;
+        context.now = now;
+        context.scale = self.scale;
+        context.screen = self.screen;
+        context.focus = focus;
+        {
+            span!("dispatch", events = events.len());
+            let started = Instant::now();
+            frame.events = events.len();
+            for event in events {
+                trace!(?event, "dispatch");
+                #[cfg(feature = "inspector")]
+                if let Some(inspector) = &mut self.inspector {inspector.count(&*event);}
+                context.services.offer(&*event);
+                context.state.get_or_default::<cursor::Cursor>().track(&*event);
+                context.state.get_or_default::<touch::Touches>().track(&*event);
+                context.focus.key(&*event);
+                let event = context.state.get_or_default::<portal::Modals>().capture(event);
+                if let Some(event) = event
+                    .pass(&mut context, &[prism::layout::Area{offset: (0.0, 0.0), size: self.size.0}])
+                    .remove(0)
+                {
+                    self.app.event(&mut context, &self.size, event);
+                }
             }
+            frame.dispatch = started.elapsed();
+        }
+        context.flush_keyboard();
+        let values = context.state.take_dirty();
+        context.save(values);
+        self.events = context.events;
+        self.events.extend(context.state.take_changes());
+        if !context.tasks.is_empty() {std::task::Wake::wake_by_ref(&self.waker);}
+        self.tasks.extend(context.tasks);
+        self.backpressure = context.backpressure.unwrap_or(self.backpressure);
+        self.services = context.services;
+        self.focus = context.focus;
+        self.redraw = redraw || context.redraw;
+        self.state = context.state;
+        font::start_frame(self.state.get::<font::Fonts>());
+        if stale || self.redraw || !self.lazy_layout {
+            span!("layout");
+            let started = Instant::now();
+            portal::start_layout(self.screen);
+            std::mem::replace(&mut self.request, self.app.request_size()).recycle();
+            std::mem::replace(&mut self.size, self.app.build(self.screen, &self.request)).recycle();
+            frame.layout = started.elapsed();
+            frame.nodes = metrics::nodes(&self.request);
+        }
+        self.state.get_or_default::<caret::Caret>().start_draw();
+        portal::start_draw();
+        let mut instructions = {
+            span!("draw");
+            let started = Instant::now();
+            let instructions = self.app.draw(&self.size, (0.0, 0.0), (0.0, 0.0, self.screen.0, self.screen.1));
+            frame.draw = started.elapsed();
+            instructions
+        };
+        let (moved, reveal) = self.state.get_or_default::<caret::Caret>().finish_draw();
+        if let Some(rect) = moved {handler.set_caret_rect(rect);}
+        if let Some(rect) = reveal {
+            self.events.push(Box::new(caret::RevealCaret(rect)));
+            self.redraw = true;
+        }
+        instructions.extend(portal::finish_draw());
+        let style = self.state.get::<theme::Theme>().map(|theme| theme.focus_ring).unwrap_or(theme::Theme::default().focus_ring);
+        instructions.extend(self.focus.ring(style));
+        let cursor = self.state.get_or_default::<cursor::Cursor>();
+        if let Some(visible) = cursor.visibility() {handler.set_cursor_visible(visible);}
+        instructions.extend(cursor.draw(self.screen));
+        #[cfg(feature = "inspector")]
+        if let Some(inspector) = &mut self.inspector {
+            let (outline, injected) = inspector.poll(&*self.app, &self.request, &self.size, &instructions);
+            instructions.extend(outline);
+            self.redraw |= !injected.is_empty();
+            self.events.extend(injected);
         }
-        self.events = context.1;
-        self.request = self.app.request_size();
-        self.size = self.app.build(self.screen, &self.request);
-        self.app.draw(&self.size, (0.0, 0.0), (0.0, 0.0, self.screen.0, self.screen.1))
+        frame.instructions = instructions.len();
+        self.state.get_or_default::<metrics::Metrics>().push(frame);
+        instructions
     }
 }
 
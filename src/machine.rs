@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::event::Event;
+
+type Hook = Arc<dyn Fn() -> Vec<Box<dyn Event>>>;
+
+/// A finite state machine for the states of a component, driven by inputs from its `on_event`.
+///
+/// Transitions are listed up front, and entering or leaving a state can produce the events the component
+/// passes on to its children, so interaction states live in one place instead of loose flags.
+///
+///```rust
+/// let machine = Machine::new(Toggle::Off)
+///     .on(Toggle::Off, Input::Press, Toggle::On)
+///     .on(Toggle::On, Input::Press, Toggle::Off)
+///     .on_enter(Toggle::On, || events![event::Toggled(true)])
+///     .on_enter(Toggle::Off, || events![event::Toggled(false)]);
+///```
+#[derive(Clone)]
+pub struct Machine<S, E> {
+    state: S,
+    /// `(from, input, to)`, where a `from` of `None` matches every state.
+    table: Vec<(Option<S>, E, S)>,
+    enter: Vec<(S, Hook)>,
+    exit: Vec<(S, Hook)>,
+}
+
+impl<S: Copy + PartialEq + Debug, E: PartialEq + Debug> Machine<S, E> {
+    pub fn new(initial: S) -> Self {Machine{state: initial, table: vec![], enter: vec![], exit: vec![]}}
+
+    /// Adds a transition from `from` to `to` on `input`.
+    pub fn on(mut self, from: S, input: E, to: S) -> Self {
+        self.table.push((Some(from), input, to));
+        self
+    }
+
+    /// Adds a transition to `to` on `input` from any state without a transition of its own for it.
+    pub fn on_any(mut self, input: E, to: S) -> Self {
+        self.table.push((None, input, to));
+        self
+    }
+
+    /// Runs `hook` whenever the machine enters `state`.
+    pub fn on_enter(mut self, state: S, hook: impl Fn() -> Vec<Box<dyn Event>> + 'static) -> Self {
+        self.enter.push((state, Arc::new(hook)));
+        self
+    }
+
+    /// Runs `hook` whenever the machine leaves `state`.
+    pub fn on_exit(mut self, state: S, hook: impl Fn() -> Vec<Box<dyn Event>> + 'static) -> Self {
+        self.exit.push((state, Arc::new(hook)));
+        self
+    }
+
+    pub fn state(&self) -> S {self.state}
+
+    pub fn is(&self, state: S) -> bool {self.state == state}
+
+    /// Returns the state `input` would move to, if it moves at all.
+    pub fn next(&self, input: &E) -> Option<S> {
+        let matching = |exact: bool| self.table.iter().find(|(from, i, _)| i == input && match from {
+            Some(from) => exact && *from == self.state,
+            None => !exact,
+        });
+        matching(true).or_else(|| matching(false)).map(|(_, _, to)| *to).filter(|to| *to != self.state)
+    }
+
+    /// Feeds `input` to the machine, returning the events produced by the exit and entry hooks of the transition it takes.
+    pub fn fire(&mut self, input: E) -> Vec<Box<dyn Event>> {
+        let Some(to) = self.next(&input) else {return vec![]};
+        let from = std::mem::replace(&mut self.state, to);
+        let exit = self.exit.iter().filter(|(s, _)| *s == from);
+        let enter = self.enter.iter().filter(|(s, _)| *s == to);
+        exit.chain(enter).flat_map(|(_, hook)| hook()).collect()
+    }
+}
+
+impl<S: Debug, E: Debug> Debug for Machine<S, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Machine").field("state", &self.state).field("transitions", &self.table.len()).finish()
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::drawable::RequestTree;
+
+/// How many frames [`Metrics`] remembers, two seconds at 60 frames per second.
+const HISTORY: usize = 120;
+
+/// The counters of one frame drawn by [`Instance::draw`](crate::Instance::draw).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameMetrics {
+    /// The time since the frame before.
+    pub interval: Duration,
+    pub tick: Duration,
+    pub dispatch: Duration,
+    /// Zero when the layout of the frame before was reused, see [`Instance::set_lazy_layout`](crate::Instance::set_lazy_layout).
+    pub layout: Duration,
+    pub draw: Duration,
+    /// Events delivered to the tree, not counting the tick.
+    pub events: usize,
+    /// Drawables measured during layout, zero when the layout was reused.
+    pub nodes: usize,
+    /// Instructions handed to the canvas.
+    pub instructions: usize,
+}
+
+impl FrameMetrics {
+    /// The time spent producing the frame, every phase together.
+    pub fn busy(&self) -> Duration {self.tick + self.dispatch + self.layout + self.draw}
+}
+
+/// The counters of recent frames, kept in [`Context::state`](crate::Context::state), for an FPS or jank meter
+/// in the app and for tests asserting a screen stays within its budget.
+///
+///```rust
+/// let metrics = ctx.metrics();
+/// label.spans[0].text = format!("{:.0} fps, {} slow", metrics.fps(), metrics.over_budget(Duration::from_millis(16)));
+///```
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    frames: VecDeque<FrameMetrics>,
+}
+
+impl Metrics {
+    /// The most recent frame, or all zeros before the first.
+    pub fn last(&self) -> FrameMetrics {self.frames.back().copied().unwrap_or_default()}
+
+    /// Recent frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &FrameMetrics> {self.frames.iter()}
+
+    /// Frames per second over the recent frames.
+    pub fn fps(&self) -> f32 {
+        let total = self.frames.iter().map(|frame| frame.interval).sum::<Duration>().as_secs_f32();
+        if total > 0.0 {self.frames.len() as f32 / total} else {0.0}
+    }
+
+    /// The recent frame that took longest to produce.
+    pub fn worst(&self) -> Option<FrameMetrics> {self.frames.iter().max_by_key(|frame| frame.busy()).copied()}
+
+    /// How many recent frames took longer than `budget` to produce.
+    pub fn over_budget(&self, budget: Duration) -> usize {self.frames.iter().filter(|frame| frame.busy() > budget).count()}
+
+    pub(crate) fn push(&mut self, frame: FrameMetrics) {
+        if self.frames.len() == HISTORY {self.frames.pop_front();}
+        self.frames.push_back(frame);
+    }
+}
+
+/// Counts the drawables in a measured tree.
+pub(crate) fn nodes(tree: &RequestTree) -> usize {1 + tree.1.iter().map(nodes).sum::<usize>()}
@@ -0,0 +1,198 @@
+use std::cell::{Cell, RefCell};
+
+use crate::canvas::Instruction;
+use crate::drawable::{Drawable, RequestTree, SizedTree, Offset, Rect, Size};
+use crate::event::{Event, MouseEvent, KeyboardEvent, TouchEvent, broadcast};
+use crate::layout::Area;
+use crate::accessibility::AccessNode;
+use crate::i18n::Direction;
+use crate::Context;
+
+thread_local! {
+    static SCREEN: Cell<Size> = const {Cell::new((0.0, 0.0))};
+    static LAYER: RefCell<Vec<Instruction>> = const {RefCell::new(Vec::new())};
+}
+
+/// Sets the screen size layers are laid out and kept within, before the app is built.
+pub(crate) fn start_layout(screen: Size) {SCREEN.set(screen);}
+
+pub(crate) fn start_draw() {LAYER.with_borrow_mut(Vec::clear);}
+
+/// Takes everything portals drew into the top layer this frame, in the order they were drawn.
+pub(crate) fn finish_draw() -> Vec<Instruction> {LAYER.take()}
+
+/// Where a [`Portal`] places its layer around the child it is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    #[default]
+    Below,
+    Above,
+    /// Before the child along the reading direction, to its left unless in a right to left subtree.
+    Start,
+    End,
+    /// On top of the child, sharing its top left corner.
+    Over,
+}
+
+/// Anchors a layer, such as a tooltip, dropdown menu or modal, to its child and draws it above the whole app.
+///
+/// The layer is laid out with the rest of the tree but drawn after it, outside the bounds of every parent, and
+/// moved back onto the screen when its placement would push it off an edge. Pointer events over the layer are
+/// passed to it instead of the child. Drawables laid out elsewhere beneath the layer still see them, so a menu
+/// over other content should close on [`Overlay`](crate::display::Overlay)'s dismiss or swallow presses itself.
+///
+///```rust
+/// let mut button = Portal::new(button, Placement::Below);
+/// button.open(menu);
+///```
+#[derive(Debug, Clone)]
+pub struct Portal<D: Drawable + Clone, L: Drawable + Clone> {
+    child: D,
+    layer: Option<L>,
+    placement: Placement,
+    gap: f32,
+    /// Where the portal and its layer were last drawn, the portal from the top left of the screen and the layer from the portal.
+    placed: Cell<Option<(Offset, Offset)>>,
+}
+
+impl<D: Drawable + Clone, L: Drawable + Clone> Portal<D, L> {
+    pub fn new(child: D, placement: Placement) -> Self {
+        Portal{child, layer: None, placement, gap: 0.0, placed: Cell::new(None)}
+    }
+
+    /// Sets the space left between the child and the layer.
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn open(&mut self, layer: L) {self.layer = Some(layer);}
+    pub fn close(&mut self) -> Option<L> {self.layer.take()}
+    pub fn is_open(&self) -> bool {self.layer.is_some()}
+    pub fn layer(&mut self) -> Option<&mut L> {self.layer.as_mut()}
+    pub fn inner(&mut self) -> &mut D {&mut self.child}
+    pub fn placement(&self) -> Placement {self.placement}
+    pub fn set_placement(&mut self, placement: Placement) {self.placement = placement;}
+
+    fn offset(&self, anchor: Size, layer: Size) -> Offset {
+        let (start, end) = (-layer.0 - self.gap, anchor.0 + self.gap);
+        let (start, end) = match Direction::current() {
+            Direction::Ltr => (start, end),
+            Direction::Rtl => (end, start),
+        };
+        match self.placement {
+            Placement::Below => (0.0, anchor.1 + self.gap),
+            Placement::Above => (0.0, -layer.1 - self.gap),
+            Placement::Start => (start, 0.0),
+            Placement::End => (end, 0.0),
+            Placement::Over => (0.0, 0.0),
+        }
+    }
+}
+
+impl<D: Drawable + Clone + 'static, L: Drawable + Clone + 'static> Drawable for Portal<D, L> {
+    fn request_size(&self) -> RequestTree {
+        let child = self.child.request_size();
+        RequestTree(child.0, RequestTree::branches([child, self.layer.request_size()]))
+    }
+
+    fn build(&self, size: Size, request: &RequestTree) -> SizedTree {
+        let child = self.child.build(size, &request.1[0]);
+        let layer = self.layer.build(request.1[1].0.get(SCREEN.get()), &request.1[1]);
+        let offset = self.offset(child.0, layer.0);
+        SizedTree::new(child.0, SizedTree::branches([((0.0, 0.0), child), (offset, layer)]))
+    }
+
+    fn draw(&self, sized: &SizedTree, offset: Offset, bound: Rect) -> Vec<Instruction> {
+        let instructions = self.child.draw(&sized.1[0].1, offset, bound);
+        let (placed, layer) = &sized.1[1];
+        match &self.layer {
+            Some(drawable) => {
+                let screen = SCREEN.get();
+                let at = (
+                    (offset.0 + placed.0).min(screen.0 - layer.0.0).max(0.0),
+                    (offset.1 + placed.1).min(screen.1 - layer.0.1).max(0.0),
+                );
+                self.placed.set(Some((offset, (at.0 - offset.0, at.1 - offset.1))));
+                let drawn = drawable.draw(layer, at, (0.0, 0.0, screen.0, screen.1));
+                LAYER.with_borrow_mut(|top| top.extend(drawn));
+            },
+            None => self.placed.set(None),
+        }
+        instructions
+    }
+
+    fn name(&self) -> String {self.child.name()}
+
+    fn event(&mut self, ctx: &mut Context, sized: &SizedTree, event: Box<dyn Event>) {
+        let Some((origin, placed)) = self.placed.get().filter(|_| self.layer.is_some()) else {
+            return self.child.event(ctx, &sized.1[0].1, event);
+        };
+        // Positions outside the child never reach the portal, so they are taken again from the pointer.
+        let pointer = ctx.state.get_or_default::<crate::cursor::Cursor>().position();
+        let event = match (event.downcast_ref::<MouseEvent>(), pointer) {
+            (Some(MouseEvent{state, ..}), Some(pointer)) => {
+                Box::new(MouseEvent{position: Some((pointer.0 - origin.0, pointer.1 - origin.1)), state: *state}) as Box<dyn Event>
+            },
+            _ => event,
+        };
+        let areas = [Area{offset: (0.0, 0.0), size: sized.1[0].1.0}, Area{offset: placed, size: sized.1[1].1.0}];
+        let mut passed = event.pass(ctx, &areas).into_iter();
+        if let Some(event) = passed.next().flatten() {self.child.event(ctx, &sized.1[0].1, event);}
+        if let Some(event) = passed.next().flatten() {self.layer.event(ctx, &sized.1[1].1, event);}
+    }
+
+    fn accessibility(&self, sized: &SizedTree, offset: Offset) -> Vec<AccessNode> {
+        let mut nodes = self.child.accessibility(&sized.1[0].1, offset);
+        if let Some((_, placed)) = self.placed.get() {
+            nodes.extend(self.layer.accessibility(&sized.1[1].1, (offset.0 + placed.0, offset.1 + placed.1)));
+        }
+        nodes
+    }
+
+    fn inspect(&self) -> Vec<(Option<usize>, &dyn Drawable)> {
+        let mut children = vec![(Some(0), &self.child as &dyn Drawable)];
+        if let Some(layer) = &self.layer {children.push((Some(1), layer));}
+        children
+    }
+}
+
+/// The open modal layers in tree order, kept in [`Context::state`](crate::Context::state) and refilled every tick
+/// so a modal that leaves the tree stops holding input back.
+#[derive(Debug, Clone, Default)]
+pub struct Modals(Vec<uuid::Uuid>);
+
+impl Modals {
+    /// The modal that receives input, the last one opened in tree order.
+    pub fn top(&self) -> Option<uuid::Uuid> {self.0.last().copied()}
+    pub fn is_empty(&self) -> bool {self.0.is_empty()}
+
+    pub(crate) fn clear(&mut self) {self.0.clear();}
+    pub(crate) fn push(&mut self, id: uuid::Uuid) {self.0.push(id);}
+
+    /// Holds pointer, touch and key events back from the app while a modal is open.
+    pub(crate) fn capture(&self, event: Box<dyn Event>) -> Box<dyn Event> {
+        if self.is_empty() {return event;}
+        match event.downcast::<MouseEvent>() {
+            Ok(mouse) => Box::new(Captured::Mouse(*mouse)),
+            Err(event) => match event.downcast::<KeyboardEvent>() {
+                Ok(key) => Box::new(Captured::Keyboard(*key)),
+                Err(event) => match event.downcast::<TouchEvent>() {
+                    Ok(touch) => Box::new(Captured::Touch(*touch)),
+                    Err(event) => event,
+                }
+            }
+        }
+    }
+}
+
+/// A pointer, touch or key event held back while a modal is open. Only the topmost modal unwraps it, so nothing else in
+/// the tree sees the input.
+#[derive(Debug, Clone)]
+pub(crate) enum Captured {Mouse(MouseEvent), Keyboard(KeyboardEvent), Touch(TouchEvent)}
+
+impl Event for Captured {
+    fn pass(self: Box<Self>, _ctx: &mut Context, children: &[Area]) -> Vec<Option<Box<dyn Event>>> {
+        broadcast(self, children)
+    }
+}
@@ -0,0 +1,54 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::event::{Event, LowPowerChanged, PowerChanged, WindowStateChanged};
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const {Cell::new(false)};
+}
+
+/// The shortest time between frames that only animations asked for while in low power mode, about 30 per second.
+pub const LOW_POWER_FRAME: Duration = Duration::from_millis(33);
+
+/// Whether the app is in low power mode this frame, for components to skip work that is only decoration.
+///
+/// Meaningful while the tree is ticked, handling events or drawn. While it is set, animation frames are
+/// throttled to [`LOW_POWER_FRAME`], [`Animated`](crate::animation::Animated) values and
+/// [`Timeline`](crate::animation::Timeline)s not marked essential skip to their end or pause, and buttons
+/// don't show hover.
+pub fn is_low_power() -> bool {ACTIVE.get()}
+
+/// Low power mode, kept in [`Context::state`](crate::Context::state).
+///
+/// It turns on by itself while the system's battery saver is on or the app isn't visible, unless overridden
+/// with [`Context::set_low_power`](crate::Context::set_low_power), and each change is broadcast as a [`LowPowerChanged`].
+#[derive(Debug, Clone, Default)]
+pub struct LowPower {
+    forced: Option<bool>,
+    battery: bool,
+    hidden: bool,
+    minimized: bool,
+    active: bool,
+}
+
+impl LowPower {
+    pub fn is_active(&self) -> bool {self.active}
+
+    pub(crate) fn force(&mut self, forced: Option<bool>) {self.forced = forced;}
+
+    pub(crate) fn set_battery(&mut self, low_power: bool) {self.battery = low_power;}
+
+    pub(crate) fn set_hidden(&mut self, hidden: bool) {self.hidden = hidden;}
+
+    pub(crate) fn observe(&mut self, event: &dyn Event) {
+        if let Some(PowerChanged(state)) = event.downcast_ref::<PowerChanged>() {self.battery = state.low_power;}
+        if let Some(WindowStateChanged(state)) = event.downcast_ref::<WindowStateChanged>() {self.minimized = state.minimized;}
+    }
+
+    /// Settles the mode for the coming frame, returning the event announcing it if it changed.
+    pub(crate) fn update(&mut self) -> Option<Box<dyn Event>> {
+        let active = self.forced.unwrap_or(self.battery || self.hidden || self.minimized);
+        ACTIVE.set(active);
+        (active != std::mem::replace(&mut self.active, active)).then(|| Box::new(LowPowerChanged(active)) as Box<dyn Event>)
+    }
+}
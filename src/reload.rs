@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::drawable::{Drawable, Component, SizedTree};
+use crate::event::{OnEvent, Event, TickEvent};
+use crate::layout::Stack;
+use crate::Context;
+
+pub(crate) type Builder = Rc<dyn Fn(&mut Context) -> Box<dyn Drawable>>;
+
+/// The latest builder of every [`Reloadable`] id, kept in [`Context::state`] so it outlives the drawables it builds.
+#[derive(Default)]
+pub(crate) struct Registry(HashMap<String, (Builder, u64)>);
+
+impl Registry {
+    pub(crate) fn swap(&mut self, id: &str, builder: Builder) {
+        let generation = self.0.get(id).map(|(_, g)| g + 1).unwrap_or_default();
+        self.0.insert(id.to_string(), (builder, generation));
+    }
+}
+
+/// A subtree that can be rebuilt from a new builder while the app runs, for iterating on UI without a restart.
+///
+/// Calling [`Context::reload`] or [`Instance::reload`](crate::Instance::reload) with the same id rebuilds every
+/// `Reloadable` using it on the next frame. [`Context::state`] is left alone, so the new subtree picks up where the old one was.
+///
+///```rust
+/// Reloadable::new(ctx, "settings", |ctx| Box::new(SettingsPage::new(ctx)))
+///```
+#[derive(Debug, Component, Clone)]
+pub struct Reloadable(Stack, Box<dyn Drawable>, #[skip] String, #[skip] u64);
+
+impl Reloadable {
+    /// Builds the subtree with `builder`, registering it under `id` unless a reload already replaced it.
+    pub fn new(ctx: &mut Context, id: &str, builder: impl Fn(&mut Context) -> Box<dyn Drawable> + 'static) -> Self {
+        let registry = ctx.state.get_or_default::<Registry>();
+        if !registry.0.contains_key(id) {registry.swap(id, Rc::new(builder));}
+        let (builder, generation) = registry.0[id].clone();
+        Reloadable(Stack::default(), builder(ctx), id.to_string(), generation)
+    }
+
+    pub fn id(&self) -> &str {&self.2}
+}
+
+impl OnEvent for Reloadable {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        if event.is::<TickEvent>() {
+            let latest = ctx.state.get::<Registry>().and_then(|r| r.0.get(&self.2)).cloned();
+            if let Some((builder, generation)) = latest && generation != self.3 {
+                self.1 = builder(ctx);
+                self.3 = generation;
+            }
+        }
+        vec![event]
+    }
+}
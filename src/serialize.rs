@@ -0,0 +1,125 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use downcast_rs::Downcast;
+
+use crate::event::{self, Event};
+
+/// An event as written out by an [`EventRegistry`], its registered name alongside its serialized fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recorded {
+    pub name: String,
+    pub event: Value,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    name: &'static str,
+    save: fn(&dyn Any) -> Option<Value>,
+    load: fn(Value) -> Option<Box<dyn Event>>,
+}
+
+impl Entry {
+    fn new<E: Event + Serialize + DeserializeOwned>(name: &'static str) -> Self {
+        Entry {
+            name,
+            save: |event| event.downcast_ref::<E>().and_then(|e| serde_json::to_value(e).ok()),
+            load: |value| serde_json::from_value::<E>(value).ok().map(|e| Box::new(e) as Box<dyn Event>),
+        }
+    }
+}
+
+/// Turns `Box<dyn Event>` to and from JSON by the name each event type was registered under, for recording
+/// and replaying sessions, the remote inspector and sending events between processes.
+///
+/// The default registry knows the input, host and widget events in [`event`]. App events are added with
+/// [`EventRegistry::register`] under names that stay the same between builds.
+///
+///```rust
+/// let mut registry = EventRegistry::default();
+/// registry.register::<Saved>("app.saved");
+///
+/// let line = serde_json::to_string(&registry.serialize(&*event).unwrap()).unwrap();
+/// let replayed: Option<Box<dyn Event>> = registry.deserialize(serde_json::from_str(&line).unwrap());
+///```
+#[derive(Clone)]
+pub struct EventRegistry {
+    types: HashMap<TypeId, Entry>,
+    names: HashMap<&'static str, TypeId>,
+}
+
+impl EventRegistry {
+    /// A registry without any events, not even the built in ones.
+    pub fn empty() -> Self {EventRegistry{types: HashMap::new(), names: HashMap::new()}}
+
+    /// Registers `E` under `name`, replacing whatever was registered under it before.
+    pub fn register<E: Event + Serialize + DeserializeOwned>(&mut self, name: &'static str) {
+        if let Some(old) = self.names.insert(name, TypeId::of::<E>()) {self.types.remove(&old);}
+        self.types.insert(TypeId::of::<E>(), Entry::new::<E>(name));
+    }
+
+    pub fn is_registered(&self, event: &dyn Event) -> bool {self.types.contains_key(&event.as_any().type_id())}
+
+    /// Returns `event` as a [`Recorded`], or `None` if its type isn't registered.
+    pub fn serialize(&self, event: &dyn Event) -> Option<Recorded> {
+        let entry = self.types.get(&event.as_any().type_id())?;
+        Some(Recorded{name: entry.name.to_string(), event: (entry.save)(event.as_any())?})
+    }
+
+    /// Rebuilds the event in `recorded`, or returns `None` if its name isn't registered or its fields don't match.
+    pub fn deserialize(&self, recorded: Recorded) -> Option<Box<dyn Event>> {
+        let entry = self.names.get(recorded.name.as_str()).and_then(|id| self.types.get(id))?;
+        (entry.load)(recorded.event)
+    }
+}
+
+impl Default for EventRegistry {
+    fn default() -> Self {
+        let mut registry = EventRegistry::empty();
+        registry.register::<event::MouseEvent>("MouseEvent");
+        registry.register::<event::TouchEvent>("TouchEvent");
+        registry.register::<event::KeyboardEvent>("KeyboardEvent");
+        registry.register::<event::TickEvent>("TickEvent");
+        registry.register::<event::NotificationTapped>("NotificationTapped");
+        registry.register::<event::LocationUpdate>("LocationUpdate");
+        registry.register::<event::FileSaved>("FileSaved");
+        registry.register::<event::UrlOpened>("UrlOpened");
+        registry.register::<event::CloudConflict>("CloudConflict");
+        registry.register::<event::CloudValue>("CloudValue");
+        registry.register::<event::ClipboardContents>("ClipboardContents");
+        registry.register::<event::AppearanceChanged>("AppearanceChanged");
+        registry.register::<event::WindowStateChanged>("WindowStateChanged");
+        registry.register::<event::IdleEntered>("IdleEntered");
+        registry.register::<event::IdleExited>("IdleExited");
+        registry.register::<event::LowPowerChanged>("LowPowerChanged");
+        registry.register::<event::LocaleChanged>("LocaleChanged");
+        registry.register::<event::PowerChanged>("PowerChanged");
+        registry.register::<event::NetworkChanged>("NetworkChanged");
+        registry.register::<event::PermissionResult>("PermissionResult");
+        registry.register::<event::Button>("Button");
+        registry.register::<event::Selectable>("Selectable");
+        registry.register::<event::Slider>("Slider");
+        registry.register::<event::TextInput>("TextInput");
+        registry.register::<event::NumericalInput>("NumericalInput");
+        registry.register::<event::Drawer>("Drawer");
+        registry.register::<event::TableView>("TableView");
+        registry.register::<event::PaginatedList>("PaginatedList");
+        registry.register::<event::Overlay>("Overlay");
+        registry.register::<event::Modal>("Modal");
+        registry.register::<event::Drag>("Drag");
+        registry.register::<event::Zoom>("Zoom");
+        registry.register::<event::Focused>("Focused");
+        registry.register::<event::TimelineFinished>("TimelineFinished");
+        registry.register::<event::ScrollFinished>("ScrollFinished");
+        registry.register::<event::HistoryChanged>("HistoryChanged");
+        registry
+    }
+}
+
+impl std::fmt::Debug for EventRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.names.keys()).finish()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Serialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+use crate::event::{Event, ServiceResponse};
+
+/// A typed backend call, identified by its `NAME` and serialized as JSON at the boundary.
+///
+///```rust
+/// struct Weather;
+/// impl Service for Weather {
+///     const NAME: &'static str = "weather";
+///     type Request = String;
+///     type Response = f32;
+/// }
+///
+/// let id = ctx.call::<Weather>("Lisbon".to_string());
+///```
+///
+/// Calls are answered by a local implementation registered with [`Context::register_service`](crate::Context::register_service)
+/// or else forwarded to the host through [`Handler::call_service`](crate::Handler::call_service), which answers through
+/// [`Instance::respond`](crate::Instance::respond). Either way the answer is broadcast as a [`ServiceResponse<S>`]
+/// carrying the id returned by [`Context::call`](crate::Context::call).
+pub trait Service: 'static {
+    const NAME: &'static str;
+    type Request: Serialize + DeserializeOwned;
+    type Response: Serialize + DeserializeOwned + Clone + Debug + 'static;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceError {
+    /// The service reported a failure.
+    Failed(String),
+    /// The request or response did not match the service's message types.
+    Malformed(String),
+}
+
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceError::Failed(error) => write!(f, "Service failed: {error}"),
+            ServiceError::Malformed(error) => write!(f, "Malformed service message: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+/// Resolves with the event answering a query started by [`Context::request`](crate::Context::request).
+///
+/// Like every future it does nothing unless polled, usually by passing it to [`Context::spawn`](crate::Context::spawn).
+pub struct Answer<E>(Rc<RefCell<(Option<E>, Option<Waker>)>>);
+
+impl<E> Future for Answer<E> {
+    type Output = E;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<E> {
+        let mut slot = self.0.borrow_mut();
+        match slot.0.take() {
+            Some(event) => Poll::Ready(event),
+            None => {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<E> Debug for Answer<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {write!(f, "Answer<{}>", std::any::type_name::<E>())}
+}
+
+/// Where [`Services::call`] sent a call.
+pub(crate) enum Routed {
+    /// Forwarded to the host with this payload, awaiting [`Services::respond`].
+    Remote(String),
+    /// Answered in process, or refused before it could be sent, with this response.
+    Local(Box<dyn Event>),
+}
+
+type Local = Box<dyn Fn(&str) -> Result<String, ServiceError>>;
+type Decode = fn(Uuid, Result<String, String>) -> Box<dyn Event>;
+type Waiter = Box<dyn FnMut(&dyn Event) -> bool>;
+
+/// Locally registered services and the calls and queries still awaiting a response.
+#[derive(Default)]
+pub(crate) struct Services {
+    local: HashMap<&'static str, Local>,
+    pending: HashMap<Uuid, Decode>,
+    waiting: Vec<Waiter>,
+}
+
+impl Services {
+    pub(crate) fn register<S: Service>(&mut self, f: impl Fn(S::Request) -> S::Response + 'static) {
+        self.local.insert(S::NAME, Box::new(move |payload| {
+            let request = serde_json::from_str(payload).map_err(|e| ServiceError::Malformed(e.to_string()))?;
+            serde_json::to_string(&f(request)).map_err(|e| ServiceError::Malformed(e.to_string()))
+        }));
+    }
+
+    /// Answers the call locally if possible, otherwise returns the payload to forward once it is awaiting a response.
+    pub(crate) fn call<S: Service>(&mut self, id: Uuid, request: S::Request) -> Routed {
+        let payload = match serde_json::to_string(&request) {
+            Ok(payload) => payload,
+            Err(e) => return Routed::Local(Box::new(ServiceResponse::<S>(id, Err(ServiceError::Malformed(e.to_string()))))),
+        };
+        match self.local.get(S::NAME) {
+            Some(local) => Routed::Local(Box::new(ServiceResponse::<S>(id, local(&payload).and_then(decode::<S>)))),
+            None => {
+                self.pending.insert(id, |id, result| Box::new(ServiceResponse::<S>(id, result.map_err(ServiceError::Failed).and_then(decode::<S>))));
+                Routed::Remote(payload)
+            }
+        }
+    }
+
+    /// Returns an [`Answer`] resolving with the next dispatched `E` for which `matches` holds.
+    pub(crate) fn wait<E: Event + Clone>(&mut self, matches: impl Fn(&E) -> bool + 'static) -> Answer<E> {
+        let slot = Rc::new(RefCell::new((None, None)));
+        let answer = slot.clone();
+        self.waiting.push(Box::new(move |event| match event.downcast_ref::<E>() {
+            Some(event) if matches(event) => {
+                let mut slot = slot.borrow_mut();
+                slot.0 = Some(event.clone());
+                slot.1.take().into_iter().for_each(Waker::wake);
+                true
+            },
+            _ => false
+        }));
+        Answer(answer)
+    }
+
+    /// Hands a dispatched event to the queries waiting for it.
+    pub(crate) fn offer(&mut self, event: &dyn Event) {self.waiting.retain_mut(|waiter| !waiter(event));}
+
+    /// Turns the host's answer to call `id` into its typed response event, `None` if no such call is pending.
+    pub(crate) fn respond(&mut self, id: Uuid, result: Result<String, String>) -> Option<Box<dyn Event>> {
+        self.pending.remove(&id).map(|decode| decode(id, result))
+    }
+}
+
+fn decode<S: Service>(payload: String) -> Result<S::Response, ServiceError> {
+    serde_json::from_str(&payload).map_err(|e| ServiceError::Malformed(e.to_string()))
+}
+
+impl Debug for Services {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Services({} local, {} pending, {} waiting)", self.local.len(), self.pending.len(), self.waiting.len())
+    }
+}
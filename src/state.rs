@@ -0,0 +1,338 @@
+use std::collections::{HashMap, HashSet};
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::event::{Event, StateChanged, KeyChanged, HistoryChanged};
+
+/// A typed name for a value in [`State`], allowing several values of the same type to be stored.
+///
+///```rust
+/// const UNREAD: Key<u32> = Key::new("unread");
+/// const DRAFTS: Key<u32> = Key::new("drafts");
+///
+/// ctx.state.set_key(&UNREAD, 4);
+/// let drafts: Option<&u32> = ctx.state.get_key(&DRAFTS);
+///```
+pub struct Key<T>(&'static str, PhantomData<fn() -> T>);
+
+impl<T> Key<T> {
+    pub const fn new(name: &'static str) -> Self {Key(name, PhantomData)}
+    pub fn name(&self) -> &'static str {self.0}
+}
+
+impl<T> Clone for Key<T> {fn clone(&self) -> Self {*self}}
+impl<T> Copy for Key<T> {}
+impl<T> std::fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Key<{}>({:?})", std::any::type_name::<T>(), self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Slot {
+    Type(TypeId),
+    Key(TypeId, &'static str),
+}
+
+impl Slot {
+    fn of<T: 'static>() -> Self {Slot::Type(TypeId::of::<T>())}
+    fn key<T: 'static>(key: &Key<T>) -> Self {Slot::Key(TypeId::of::<T>(), key.0)}
+}
+
+#[derive(Clone, Copy)]
+struct Persist {
+    name: &'static str,
+    save: fn(&dyn Any) -> Option<String>,
+    load: fn(&str) -> Option<Box<dyn Any>>,
+}
+
+impl Persist {
+    fn new<T: Serialize + DeserializeOwned + 'static>(name: &'static str) -> Self {
+        Persist {
+            name,
+            save: |value| value.downcast_ref::<T>().and_then(|v| serde_json::to_string(v).ok()),
+            load: |data| serde_json::from_str::<T>(data).ok().map(|v| Box::new(v) as Box<dyn Any>),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Tracked {
+    clone: fn(&dyn Any) -> Box<dyn Any>,
+    event: fn(Slot) -> Box<dyn Event>,
+}
+
+impl Tracked {
+    fn new<T: Clone + 'static>() -> Self {
+        Tracked {
+            clone: |value| Box::new(value.downcast_ref::<T>().unwrap().clone()),
+            event: |slot| match slot {
+                Slot::Key(_, name) => Box::new(KeyChanged::<T>::new(name)),
+                Slot::Type(_) => Box::new(StateChanged::<T>::default()),
+            },
+        }
+    }
+}
+
+struct Change(Slot, Option<Box<dyn Any>>);
+
+#[derive(Default)]
+struct History {
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    transaction: Option<Vec<Change>>,
+    announced: (bool, bool),
+}
+
+/// Type keyed storage shared across the whole component tree.
+///
+/// One value of each type can be stored by type alone, and any number more under typed [`Key`]s.
+/// It is reachable from any component through [`Context::state`](crate::Context::state)
+/// and lives for as long as the [`Instance`](crate::Instance).
+///
+/// Once a type is watched with [`State::watch`], every [`State::set`], [`State::update`] or [`State::remove`] of that
+/// type broadcasts a [`StateChanged`] event at the start of the next dispatch, and likewise [`KeyChanged`] for keys
+/// watched with [`State::watch_key`]. Changes made through [`State::get_mut`] are not tracked.
+///
+/// Values opted in with [`Context::persist`](crate::Context::persist) are saved through the [`Handler`](crate::Handler)
+/// at the end of any frame in which they changed, and all of them again on [`Instance::save`](crate::Instance::save).
+///
+/// Values opted in with [`State::track`] record every change into an undo history, reverted with [`State::undo`]
+/// and reapplied with [`State::redo`]. Changes made inside [`State::transaction`] are undone together, and a
+/// [`HistoryChanged`] event is broadcast whenever undo or redo becomes available or unavailable.
+#[derive(Default)]
+pub struct State {
+    values: HashMap<Slot, Box<dyn Any>>,
+    watched: HashSet<Slot>,
+    changed: HashSet<Slot>,
+    pending: Vec<Box<dyn Event>>,
+    persisted: HashMap<Slot, Persist>,
+    dirty: HashSet<Slot>,
+    tracked: HashMap<Slot, Tracked>,
+    history: History,
+}
+
+impl State {
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&Slot::of::<T>()).and_then(|v| v.downcast_ref())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&Slot::of::<T>()).and_then(|v| v.downcast_mut())
+    }
+
+    /// Stores a value, returning the previous value of the same type.
+    pub fn set<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.record(Slot::of::<T>());
+        self.notify(Slot::of::<T>(), || Box::new(StateChanged::<T>::default()));
+        self.values.insert(Slot::of::<T>(), Box::new(value)).and_then(|v| v.downcast().ok()).map(|v| *v)
+    }
+
+    /// Modifies the stored value of type `T` in place, returning `false` if there is none.
+    pub fn update<T: 'static>(&mut self, f: impl FnOnce(&mut T)) -> bool {
+        if !self.contains::<T>() {return false;}
+        self.record(Slot::of::<T>());
+        match self.get_mut::<T>() {
+            Some(value) => {
+                f(value);
+                self.notify(Slot::of::<T>(), || Box::new(StateChanged::<T>::default()));
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.record(Slot::of::<T>());
+        self.notify(Slot::of::<T>(), || Box::new(StateChanged::<T>::default()));
+        self.values.remove(&Slot::of::<T>()).and_then(|v| v.downcast().ok()).map(|v| *v)
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {self.values.contains_key(&Slot::of::<T>())}
+
+    /// Returns the stored value of type `T`, inserting the default first if there is none.
+    pub fn get_or_default<T: Default + 'static>(&mut self) -> &mut T {
+        self.values.entry(Slot::of::<T>()).or_insert_with(|| Box::new(T::default())).downcast_mut().unwrap()
+    }
+
+    /// Starts broadcasting a [`StateChanged<T>`] event whenever the value of type `T` changes.
+    pub fn watch<T: 'static>(&mut self) {self.watched.insert(Slot::of::<T>());}
+
+    pub fn unwatch<T: 'static>(&mut self) {self.watched.remove(&Slot::of::<T>());}
+
+    pub fn get_key<T: 'static>(&self, key: &Key<T>) -> Option<&T> {
+        self.values.get(&Slot::key(key)).and_then(|v| v.downcast_ref())
+    }
+
+    pub fn get_key_mut<T: 'static>(&mut self, key: &Key<T>) -> Option<&mut T> {
+        self.values.get_mut(&Slot::key(key)).and_then(|v| v.downcast_mut())
+    }
+
+    /// Stores a value under `key`, returning the previous value.
+    pub fn set_key<T: 'static>(&mut self, key: &Key<T>, value: T) -> Option<T> {
+        self.record(Slot::key(key));
+        let name = key.0;
+        self.notify(Slot::key(key), move || Box::new(KeyChanged::<T>::new(name)));
+        self.values.insert(Slot::key(key), Box::new(value)).and_then(|v| v.downcast().ok()).map(|v| *v)
+    }
+
+    /// Modifies the value stored under `key` in place, returning `false` if there is none.
+    pub fn update_key<T: 'static>(&mut self, key: &Key<T>, f: impl FnOnce(&mut T)) -> bool {
+        if !self.values.contains_key(&Slot::key(key)) {return false;}
+        self.record(Slot::key(key));
+        match self.get_key_mut(key) {
+            Some(value) => {
+                f(value);
+                let name = key.0;
+                self.notify(Slot::key(key), move || Box::new(KeyChanged::<T>::new(name)));
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn remove_key<T: 'static>(&mut self, key: &Key<T>) -> Option<T> {
+        self.record(Slot::key(key));
+        let name = key.0;
+        self.notify(Slot::key(key), move || Box::new(KeyChanged::<T>::new(name)));
+        self.values.remove(&Slot::key(key)).and_then(|v| v.downcast().ok()).map(|v| *v)
+    }
+
+    /// Starts broadcasting a [`KeyChanged<T>`] event whenever the value under `key` changes.
+    pub fn watch_key<T: 'static>(&mut self, key: &Key<T>) {self.watched.insert(Slot::key(key));}
+
+    pub fn unwatch_key<T: 'static>(&mut self, key: &Key<T>) {self.watched.remove(&Slot::key(key));}
+
+    fn notify(&mut self, slot: Slot, event: impl FnOnce() -> Box<dyn Event>) {
+        if self.persisted.contains_key(&slot) {self.dirty.insert(slot);}
+        if self.watched.contains(&slot) && self.changed.insert(slot) {
+            self.pending.push(event());
+        }
+    }
+
+    /// Registers the value of type `T` for persistence under `name` and restores it from `saved` if given.
+    pub(crate) fn persist<T: Serialize + DeserializeOwned + 'static>(&mut self, name: &'static str, saved: Option<String>) {
+        self.hydrate(Slot::of::<T>(), Persist::new::<T>(name), saved);
+    }
+
+    pub(crate) fn persist_key<T: Serialize + DeserializeOwned + 'static>(&mut self, key: &Key<T>, saved: Option<String>) {
+        self.hydrate(Slot::key(key), Persist::new::<T>(key.0), saved);
+    }
+
+    fn hydrate(&mut self, slot: Slot, persist: Persist, saved: Option<String>) {
+        if let Some(value) = saved.and_then(|data| (persist.load)(&data)) {
+            self.values.insert(slot, value);
+        }
+        self.persisted.insert(slot, persist);
+    }
+
+    /// Serializes the persisted values that changed since the last call, `None` for removed values.
+    pub(crate) fn take_dirty(&mut self) -> Vec<(&'static str, Option<String>)> {
+        let dirty = std::mem::take(&mut self.dirty);
+        dirty.into_iter().flat_map(|slot| self.serialize(slot)).collect()
+    }
+
+    /// Serializes every persisted value, `None` for values that aren't stored.
+    pub(crate) fn serialize_all(&mut self) -> Vec<(&'static str, Option<String>)> {
+        self.dirty.clear();
+        self.persisted.keys().copied().collect::<Vec<_>>().into_iter().flat_map(|slot| self.serialize(slot)).collect()
+    }
+
+    fn serialize(&self, slot: Slot) -> Option<(&'static str, Option<String>)> {
+        let persist = self.persisted.get(&slot)?;
+        Some((persist.name, self.values.get(&slot).and_then(|value| (persist.save)(&**value))))
+    }
+
+    /// Records changes to the value of type `T` in the undo history.
+    pub fn track<T: Clone + 'static>(&mut self) {self.tracked.insert(Slot::of::<T>(), Tracked::new::<T>());}
+
+    /// Records changes to the value stored under `key` in the undo history.
+    pub fn track_key<T: Clone + 'static>(&mut self, key: &Key<T>) {self.tracked.insert(Slot::key(key), Tracked::new::<T>());}
+
+    /// Runs `f`, recording all of its changes as a single undo step.
+    pub fn transaction<R>(&mut self, f: impl FnOnce(&mut State) -> R) -> R {
+        let outer = self.history.transaction.replace(Vec::new());
+        let result = f(self);
+        let changes = std::mem::replace(&mut self.history.transaction, outer).unwrap_or_default();
+        if !changes.is_empty() {
+            match &mut self.history.transaction {
+                Some(outer) => outer.extend(changes),
+                None => self.history.undo.push(changes),
+            }
+            self.announce();
+        }
+        result
+    }
+
+    pub fn can_undo(&self) -> bool {!self.history.undo.is_empty()}
+    pub fn can_redo(&self) -> bool {!self.history.redo.is_empty()}
+
+    /// Reverts the most recent undo step, returning `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(changes) = self.history.undo.pop() else {return false};
+        let redo = self.revert(changes);
+        self.history.redo.push(redo);
+        self.announce();
+        true
+    }
+
+    /// Reapplies the most recently undone step, returning `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(changes) = self.history.redo.pop() else {return false};
+        let undo = self.revert(changes);
+        self.history.undo.push(undo);
+        self.announce();
+        true
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.undo.clear();
+        self.history.redo.clear();
+        self.announce();
+    }
+
+    fn record(&mut self, slot: Slot) {
+        let Some(tracked) = self.tracked.get(&slot).copied() else {return};
+        let change = Change(slot, self.values.get(&slot).map(|value| (tracked.clone)(&**value)));
+        match &mut self.history.transaction {
+            Some(transaction) => transaction.push(change),
+            None => self.history.undo.push(vec![change]),
+        }
+        self.history.redo.clear();
+        self.announce();
+    }
+
+    fn revert(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        changes.into_iter().rev().map(|Change(slot, value)| {
+            let current = match value {
+                Some(value) => self.values.insert(slot, value),
+                None => self.values.remove(&slot),
+            };
+            if let Some(tracked) = self.tracked.get(&slot).copied() {
+                self.notify(slot, || (tracked.event)(slot));
+            }
+            Change(slot, current)
+        }).collect()
+    }
+
+    fn announce(&mut self) {
+        let available = (self.can_undo(), self.can_redo());
+        if self.history.transaction.is_none() && available != self.history.announced {
+            self.history.announced = available;
+            self.pending.push(Box::new(HistoryChanged{can_undo: available.0, can_redo: available.1}));
+        }
+    }
+
+    pub(crate) fn take_changes(&mut self) -> Vec<Box<dyn Event>> {
+        self.changed.clear();
+        std::mem::take(&mut self.pending)
+    }
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "State({} values)", self.values.len())
+    }
+}
@@ -0,0 +1,291 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::canvas::Instruction;
+use crate::drawable::{Drawable, SizedTree, Rect};
+use crate::event::{Event, MouseEvent, MouseState, MouseButton, KeyboardEvent, KeyboardState, Key, Modifiers, TouchEvent, TouchPhase};
+use crate::layout::Area;
+use crate::*;
+
+/// A platform call made through [`Context`], recorded by [`Headless`] as the method name and its debug formatted arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+pub struct Call(pub &'static str, pub String);
+
+/// A camera that never produces frames, returned by [`Headless::start_camera`].
+#[derive(Debug, Clone)]
+pub struct NoCamera;
+
+/// A [`Handler`] without a platform behind it, for driving an [`Instance`] in tests.
+///
+/// Queries answer from its public fields and from in memory clipboard, cloud and secure storage, and every
+/// call is recorded for [`Harness::calls`] to assert on.
+pub struct Headless {
+    air: air::Context,
+    calls: RefCell<Vec<Call>>,
+    clipboard: RefCell<Option<String>>,
+    cloud: RefCell<HashMap<String, String>>,
+    secure: RefCell<HashMap<String, String>>,
+    window: Cell<WindowState>,
+    pub permission: PermissionStatus,
+    pub safe_area: (f32, f32, f32, f32),
+    pub appearance: Appearance,
+    pub locale: Locale,
+    pub power: PowerState,
+    pub network: NetworkStatus,
+}
+
+impl Headless {
+    pub fn new(air: air::Context) -> Self {
+        Headless {
+            air,
+            calls: RefCell::default(),
+            clipboard: RefCell::default(),
+            cloud: RefCell::default(),
+            secure: RefCell::default(),
+            window: Cell::new(WindowState{focused: true, ..WindowState::default()}),
+            permission: PermissionStatus::Granted,
+            safe_area: (0.0, 0.0, 0.0, 0.0),
+            appearance: Appearance{color_scheme: ColorScheme::Light, text_scale: 1.0, reduce_motion: false, high_contrast: false},
+            locale: Locale{
+                languages: vec!["en-US".to_string()],
+                region: Some("US".to_string()),
+                uses_24_hour: false,
+                timezone: "UTC".to_string(),
+                utc_offset: 0
+            },
+            power: PowerState{level: Some(1.0), charging: false, low_power: false},
+            network: NetworkStatus::Online,
+        }
+    }
+
+    fn record(&self, method: &'static str, args: impl Debug) {
+        self.calls.borrow_mut().push(Call(method, format!("{args:?}")));
+    }
+}
+
+impl Handler for Headless {
+    fn air(&self) -> &air::Context {&self.air}
+    fn check_permission(&self, permission: Permission) -> PermissionStatus {self.record("check_permission", permission); self.permission}
+    fn request_permission(&self, permission: Permission) {self.record("request_permission", permission)}
+    fn start_camera(&self, settings: FrameSettings) -> Box<dyn Camera> {self.record("start_camera", settings); Box::new(NoCamera)}
+    fn cameras(&self) -> Vec<CameraInfo> {self.record("cameras", ()); vec![]}
+    fn select_camera(&self, facing: Facing) {self.record("select_camera", facing)}
+    fn set_torch(&self, on: bool) {self.record("set_torch", on)}
+    fn pick_photo(&self, options: PhotoOptions) {self.record("pick_photo", options)}
+    fn capture_screen(&self, target: CaptureTarget) {self.record("capture_screen", target)}
+
+    fn get_safe_area(&self) -> (f32, f32, f32, f32) {self.safe_area}
+    fn get_appearance(&self) -> Appearance {self.appearance}
+    fn get_locale(&self) -> Locale {self.locale.clone()}
+    fn get_power_state(&self) -> PowerState {self.power}
+    fn get_network_status(&self) -> NetworkStatus {self.network}
+    fn share_social(&self, data: String) {self.record("share_social", data)}
+    fn open_url(&self, url: String) {self.record("open_url", url)}
+
+    fn system_font(&self, family: String) -> Option<Vec<u8>> {self.record("system_font", family); None}
+
+    fn set_clipboard(&self, data: String) {self.record("set_clipboard", &data); *self.clipboard.borrow_mut() = Some(data);}
+    fn get_clipboard(&self) -> Option<String> {self.clipboard.borrow().clone()}
+
+    fn trigger_haptic(&self, haptic: Haptic) {self.record("trigger_haptic", haptic)}
+
+    fn show_keyboard(&self, kind: KeyboardKind) {self.record("show_keyboard", kind)}
+    fn hide_keyboard(&self) {self.record("hide_keyboard", ())}
+    fn set_caret_rect(&self, rect: Option<Rect>) {self.record("set_caret_rect", rect)}
+    fn set_cursor_visible(&self, visible: bool) {self.record("set_cursor_visible", visible)}
+
+    fn lock_orientation(&self, orientation: ScreenOrientation) {self.record("lock_orientation", orientation)}
+    fn unlock_orientation(&self) {self.record("unlock_orientation", ())}
+
+    fn drag_window(&self) {self.record("drag_window", ())}
+    fn resize_window(&self, edge: ResizeEdge) {self.record("resize_window", edge)}
+    fn minimize_window(&self) {self.record("minimize_window", ())}
+    fn maximize_window(&self, maximized: bool) {self.record("maximize_window", maximized); self.window.set(WindowState{maximized, ..self.window.get()})}
+    fn set_fullscreen(&self, fullscreen: bool) {self.record("set_fullscreen", fullscreen); self.window.set(WindowState{fullscreen, ..self.window.get()})}
+    fn set_always_on_top(&self, always_on_top: bool) {self.record("set_always_on_top", always_on_top); self.window.set(WindowState{always_on_top, ..self.window.get()})}
+    fn window_state(&self) -> WindowState {self.window.get()}
+    fn close_window(&self) {self.record("close_window", ())}
+    fn set_window_title(&self, title: String) {self.record("set_window_title", title)}
+    fn set_window_icon(&self, icon: image::RgbaImage) {self.record("set_window_icon", icon.dimensions())}
+
+    fn notify(&self, notification: Notification) {self.record("notify", notification)}
+    fn cancel_notification(&self, id: String) {self.record("cancel_notification", id)}
+
+    fn pick_file(&self, filters: Vec<FileFilter>, multiple: bool) {self.record("pick_file", (filters, multiple))}
+    fn save_file(&self, suggested_name: String, data: Vec<u8>) {self.record("save_file", (suggested_name, data.len()))}
+    fn read_file(&self, handle: FileHandle) -> Option<Vec<u8>> {self.record("read_file", handle); None}
+
+    fn ble_scan(&self, services: Vec<uuid::Uuid>) {self.record("ble_scan", services)}
+    fn ble_stop_scan(&self) {self.record("ble_stop_scan", ())}
+    fn ble_connect(&self, device: bluetooth::Device) {self.record("ble_connect", device)}
+    fn ble_disconnect(&self, device: bluetooth::Device) {self.record("ble_disconnect", device)}
+    fn ble_read(&self, characteristic: bluetooth::Characteristic) {self.record("ble_read", characteristic)}
+    fn ble_write(&self, characteristic: bluetooth::Characteristic, data: Vec<u8>) {self.record("ble_write", (characteristic, data))}
+    fn ble_subscribe(&self, characteristic: bluetooth::Characteristic, subscribe: bool) {self.record("ble_subscribe", (characteristic, subscribe))}
+
+    fn get_location(&self) {self.record("get_location", ())}
+    fn watch_location(&self, interval: std::time::Duration) {self.record("watch_location", interval)}
+    fn stop_location(&self) {self.record("stop_location", ())}
+
+    fn set_cloud(&self, key: String, value: String) {self.cloud.borrow_mut().insert(key, value);}
+    fn get_cloud(&self, key: String) -> Option<String> {self.cloud.borrow().get(&key).cloned()}
+    fn remove_cloud(&self, key: String) {self.cloud.borrow_mut().remove(&key);}
+    fn set_cloud_batch(&self, values: Vec<(String, String)>) {self.cloud.borrow_mut().extend(values)}
+    fn get_cloud_batch(&self, keys: Vec<String>) -> Vec<Option<String>> {keys.into_iter().map(|key| self.get_cloud(key)).collect()}
+    fn list_cloud(&self, prefix: String) -> Vec<String> {
+        self.cloud.borrow().keys().filter(|key| key.starts_with(&prefix)).cloned().collect()
+    }
+
+    fn set_secure(&self, key: String, value: String) {self.secure.borrow_mut().insert(key, value);}
+    fn get_secure(&self, key: String) -> Option<String> {self.secure.borrow().get(&key).cloned()}
+    fn remove_secure(&self, key: String) {self.secure.borrow_mut().remove(&key);}
+
+    fn call_service(&self, id: uuid::Uuid, name: String, payload: String) {self.record("call_service", (id, name, payload))}
+}
+
+/// The time between frames on the harness clock, 60 frames per second.
+pub const FRAME: Duration = Duration::from_micros(16_667);
+
+/// Runs an app without a window, for tests to feed it synthetic events and inspect its layout and platform calls.
+///
+///```rust
+/// let mut harness = Harness::new(|ctx| Button::new(label), Headless::new(air), (320.0, 480.0));
+/// harness.emit(MouseEvent{position: Some((10.0, 10.0)), state: MouseState::Pressed(MouseButton::Left)});
+/// harness.frame();
+/// assert_eq!(harness.area(&[0]).size, (320.0, 480.0));
+///```
+pub struct Harness {
+    instance: Instance,
+    handler: Box<Headless>,
+    instructions: Vec<Instruction>,
+}
+
+impl Harness {
+    pub fn new<D: Drawable>(builder: impl FnOnce(&mut Context) -> D, handler: Headless, screen: (f32, f32)) -> Self {
+        let mut handler = Box::new(handler);
+        let mut instance = Instance::new(builder, &mut *handler, screen);
+        instance.set_clock(Some(Instant::now()));
+        Harness{instance, handler, instructions: vec![]}
+    }
+
+    /// Queues an event for the next [`Harness::frame`].
+    pub fn emit<E: Event>(&mut self, event: E) {self.instance.emit(event)}
+
+    /// Ticks the app, delivers the queued events and lays out and draws the tree, then moves the clock one frame on.
+    pub fn frame(&mut self) -> &[Instruction] {
+        self.instance.clock = self.instance.clock.map(|now| now + FRAME);
+        self.instructions = self.instance.draw(&mut *self.handler);
+        &self.instructions
+    }
+
+    /// Runs `count` frames.
+    pub fn frames(&mut self, count: usize) {(0..count).for_each(|_| {self.frame();});}
+
+    /// Runs frames until `duration` has passed on the harness clock.
+    pub fn wait(&mut self, duration: Duration) {self.frames(duration.div_duration_f32(FRAME).ceil() as usize)}
+
+    /// The time on the harness clock, which [`Context::now`](crate::Context::now) returns during the last frame.
+    pub fn now(&self) -> Instant {self.instance.clock.unwrap()}
+
+    /// Moves the harness clock on by `duration` without running a frame, so the next frame sees one long gap,
+    /// as after the app was suspended.
+    pub fn advance(&mut self, duration: Duration) {self.instance.clock = self.instance.clock.map(|now| now + duration);}
+
+    /// Lays the tree out again at a new screen size.
+    pub fn resize(&mut self, screen: (f32, f32)) {self.instance.resize(screen)}
+
+    /// The instructions drawn by the last [`Harness::frame`].
+    pub fn instructions(&self) -> &[Instruction] {&self.instructions}
+
+    pub fn sized(&self) -> &SizedTree {&self.instance.size}
+
+    /// Returns the screen area of the node reached by following child indexes `path` down the [`SizedTree`].
+    pub fn area(&self, path: &[usize]) -> Area {
+        let (mut offset, mut sized) = ((0.0, 0.0), &self.instance.size);
+        for i in path {
+            let (o, branch) = &sized.1[*i];
+            offset = (offset.0 + o.0, offset.1 + o.1);
+            sized = branch;
+        }
+        Area{offset, size: sized.0}
+    }
+
+    /// Returns the app's root drawable, if it is a `D`.
+    pub fn app<D: Drawable>(&mut self) -> Option<&mut D> {self.instance.app.downcast_mut::<D>()}
+
+    pub fn state(&self) -> &State {&self.instance.state}
+
+    pub fn handler(&mut self) -> &mut Headless {&mut self.handler}
+
+    /// Every platform call made since the last [`Harness::take_calls`].
+    pub fn calls(&self) -> Vec<Call> {self.handler.calls.borrow().clone()}
+
+    pub fn take_calls(&mut self) -> Vec<Call> {self.handler.calls.take()}
+
+    fn mouse(&mut self, position: (f32, f32), state: MouseState) {
+        self.emit(MouseEvent{position: Some(position), state});
+        self.frame();
+    }
+
+    /// Presses and releases the left button at `point`, as a quick tap or click.
+    pub fn tap(&mut self, point: (f32, f32)) {
+        self.mouse(point, MouseState::Moved);
+        self.mouse(point, MouseState::Pressed(MouseButton::Left));
+        self.wait(Duration::from_millis(80));
+        self.mouse(point, MouseState::Released(MouseButton::Left));
+    }
+
+    /// Taps `point` twice within the double click window.
+    pub fn double_tap(&mut self, point: (f32, f32)) {
+        self.tap(point);
+        self.wait(Duration::from_millis(100));
+        self.tap(point);
+    }
+
+    /// Presses at `from`, moves to `to` one frame at a time over `duration` and releases there.
+    pub fn drag(&mut self, from: (f32, f32), to: (f32, f32), duration: Duration) {
+        self.mouse(from, MouseState::Pressed(MouseButton::Left));
+        let steps = duration.div_duration_f32(FRAME).ceil().max(1.0) as usize;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            self.mouse((from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t), MouseState::Moved);
+        }
+        self.mouse(to, MouseState::Released(MouseButton::Left));
+    }
+
+    /// Sends finger `id` at `position` in `phase`, then runs a frame.
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, position: (f32, f32)) {
+        self.emit(TouchEvent{id, phase, position: Some(position)});
+        self.frame();
+    }
+
+    /// Scrolls by `delta` at `point`, spread over a few frames the way a wheel or trackpad reports it.
+    pub fn scroll(&mut self, point: (f32, f32), delta: (f32, f32)) {
+        const STEPS: usize = 4;
+        (0..STEPS).for_each(|_| self.mouse(point, MouseState::Scroll(delta.0 / STEPS as f32, delta.1 / STEPS as f32)));
+    }
+
+    /// Presses and releases `key`, one frame each.
+    pub fn key(&mut self, key: Key, modifiers: Modifiers) {
+        for state in [KeyboardState::Pressed, KeyboardState::Released] {
+            self.emit(KeyboardEvent{key, state, modifiers});
+            self.frame();
+        }
+    }
+
+    /// Types `text` a character at a time, holding shift for uppercase letters.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let key = match c {
+                ' ' => Key::Space,
+                '\n' => Key::Enter,
+                '\t' => Key::Tab,
+                c => Key::Character(c),
+            };
+            self.key(key, Modifiers{shift: c.is_uppercase(), ..Modifiers::default()});
+        }
+    }
+}
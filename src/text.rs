@@ -0,0 +1,251 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::canvas::Text;
+use crate::event::{Key, Modifiers};
+
+/// Where the caret of a [`TextBuffer`] sits and where its selection started, both byte indices into its text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaretPos {
+    pub caret: usize,
+    /// The fixed end of the selection, equal to `caret` when nothing is selected.
+    pub anchor: usize,
+}
+
+impl CaretPos {
+    /// The selected byte range, empty at the caret when nothing is selected.
+    pub fn selection(&self) -> Range<usize> {self.caret.min(self.anchor)..self.caret.max(self.anchor)}
+    pub fn has_selection(&self) -> bool {self.caret != self.anchor}
+}
+
+/// The text, caret and selection of an editable field, moved and edited a grapheme cluster at a time.
+///
+/// A grapheme is what a reader sees as one character, so an emoji built from several code points, such as a
+/// family joined with zero width joiners or a hand with a skin tone modifier, is skipped over and deleted whole.
+/// Typing or deleting with text selected replaces the selection.
+///
+///```rust
+/// let mut buffer = TextBuffer::new("hello world");
+/// buffer.apply_with(Key::Left, Modifiers{control: true, shift: true, ..Modifiers::default()});
+/// assert_eq!(buffer.selected(), "world");
+///```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextBuffer {
+    text: String,
+    /// A byte index into `text`, always on a grapheme boundary.
+    caret: usize,
+    /// The other end of the selection, on a grapheme boundary and equal to `caret` when nothing is selected.
+    anchor: usize,
+}
+
+impl TextBuffer {
+    /// Creates a buffer holding `text` with the caret at the end.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        TextBuffer{caret: text.len(), anchor: text.len(), text}
+    }
+
+    pub fn text(&self) -> &str {&self.text}
+
+    /// The caret's byte index into [`TextBuffer::text`].
+    pub fn caret(&self) -> usize {self.caret}
+
+    /// The caret's position counted in graphemes, for placing it on screen.
+    pub fn caret_grapheme(&self) -> usize {self.text[..self.caret].graphemes(true).count()}
+
+    pub fn position(&self) -> CaretPos {CaretPos{caret: self.caret, anchor: self.anchor}}
+    pub fn selection(&self) -> Range<usize> {self.position().selection()}
+    pub fn has_selection(&self) -> bool {self.caret != self.anchor}
+    pub fn selected(&self) -> &str {&self.text[self.selection()]}
+
+    /// Moves the caret to the grapheme boundary at or before byte index `caret`, clearing the selection.
+    pub fn set_caret(&mut self, caret: usize) {self.move_caret(caret, false)}
+
+    /// Moves the caret to the grapheme boundary at or before byte index `caret`, selecting from where the
+    /// selection started when `extend` is set, as Shift does.
+    pub fn move_caret(&mut self, caret: usize, extend: bool) {
+        self.caret = floor_boundary(&self.text, caret);
+        if !extend {self.anchor = self.caret;}
+    }
+
+    /// Selects the graphemes within byte range `range`, leaving the caret at its end.
+    pub fn select(&mut self, range: Range<usize>) {
+        self.anchor = floor_boundary(&self.text, range.start);
+        self.caret = floor_boundary(&self.text, range.end);
+    }
+
+    pub fn select_all(&mut self) {self.select(0..self.text.len())}
+
+    /// Selects the word, run of spaces or punctuation mark under byte index `index`, as a double click does.
+    pub fn select_word(&mut self, index: usize) {self.select(word_at(&self.text, index))}
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.caret = self.text.len();
+        self.anchor = self.caret;
+    }
+
+    fn previous(&self) -> usize {
+        self.text[..self.caret].grapheme_indices(true).next_back().map(|(i, _)| i).unwrap_or(0)
+    }
+
+    fn next(&self) -> usize {
+        self.text[self.caret..].graphemes(true).next().map(|g| self.caret + g.len()).unwrap_or(self.caret)
+    }
+
+    /// Deletes the selected text, returning whether anything was selected.
+    pub fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.text.replace_range(selection.clone(), "");
+        self.set_caret(selection.start);
+        !selection.is_empty()
+    }
+
+    /// Deletes `range` unless text is selected, in which case the selection is deleted instead.
+    fn delete_range(&mut self, range: Range<usize>) -> bool {
+        if self.has_selection() {return self.delete_selection();}
+        self.text.replace_range(range.clone(), "");
+        self.set_caret(range.start);
+        !range.is_empty()
+    }
+
+    /// Inserts `text` at the caret, replacing the selection, and moves the caret past it.
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.caret, text);
+        self.set_caret(self.caret + text.len());
+    }
+
+    /// Deletes the selection or the grapheme before the caret, returning whether anything was deleted.
+    pub fn backspace(&mut self) -> bool {self.delete_range(self.previous()..self.caret)}
+
+    /// Deletes the selection or the grapheme after the caret, returning whether anything was deleted.
+    pub fn delete(&mut self) -> bool {self.delete_range(self.caret..self.next())}
+
+    /// Moves the caret back a grapheme, or to the start of the selection if there is one.
+    pub fn left(&mut self) {
+        let to = if self.has_selection() {self.selection().start} else {self.previous()};
+        self.set_caret(to);
+    }
+
+    /// Moves the caret forward a grapheme, or to the end of the selection if there is one.
+    pub fn right(&mut self) {
+        let to = if self.has_selection() {self.selection().end} else {self.next()};
+        self.set_caret(to);
+    }
+
+    /// Moves the caret to the start of the word before it, as Ctrl+Left does.
+    pub fn word_left(&mut self) {self.set_caret(previous_word(&self.text, self.caret));}
+
+    /// Moves the caret to the end of the word after it, as Ctrl+Right does.
+    pub fn word_right(&mut self) {self.set_caret(next_word(&self.text, self.caret));}
+
+    /// Deletes the selection or from the start of the word before the caret up to the caret, returning whether
+    /// anything was deleted.
+    pub fn backspace_word(&mut self) -> bool {self.delete_range(previous_word(&self.text, self.caret)..self.caret)}
+
+    pub fn home(&mut self) {self.set_caret(0);}
+
+    pub fn end(&mut self) {self.set_caret(self.text.len());}
+
+    /// Applies a key pressed without modifiers, returning whether the text changed.
+    pub fn apply(&mut self, key: Key) -> bool {self.apply_with(key, Modifiers::default())}
+
+    /// Applies a key with the modifiers held with it, returning whether the text changed.
+    ///
+    /// Shift extends the selection as the caret moves, Ctrl or Alt move and delete by word and Ctrl+A selects
+    /// everything. Other keys held with Ctrl or the super key are shortcuts and leave the buffer alone.
+    pub fn apply_with(&mut self, key: Key, modifiers: Modifiers) -> bool {
+        let (extend, word) = (modifiers.shift, modifiers.control || modifiers.alt);
+        let shortcut = modifiers.control || modifiers.supermeta;
+        match key {
+            Key::Character('a' | 'A') if shortcut => {self.select_all(); false},
+            Key::Character(_) if shortcut => false,
+            Key::Character(c) => {self.insert(c.encode_utf8(&mut [0; 4])); true},
+            Key::Space => {self.insert(" "); true},
+            Key::Backspace if word => self.backspace_word(),
+            Key::Backspace => self.backspace(),
+            Key::Delete => self.delete(),
+            Key::Left if extend => {self.move_caret(if word {previous_word(&self.text, self.caret)} else {self.previous()}, true); false},
+            Key::Right if extend => {self.move_caret(if word {next_word(&self.text, self.caret)} else {self.next()}, true); false},
+            Key::Left if word => {self.word_left(); false},
+            Key::Right if word => {self.word_right(); false},
+            Key::Left => {self.left(); false},
+            Key::Right => {self.right(); false},
+            Key::Home | Key::Up | Key::PageUp => {self.move_caret(0, extend); false},
+            Key::End | Key::Down | Key::PageDown => {self.move_caret(self.text.len(), extend); false},
+            _ => false,
+        }
+    }
+}
+
+/// Returns `text` cut off at byte index `index` of its spans joined together.
+fn prefix(text: &Text, mut index: usize) -> Text {
+    let mut text = text.clone();
+    text.spans.iter_mut().for_each(|span| {
+        let keep = index.min(span.text.len());
+        span.text.truncate(floor_boundary(&span.text, keep));
+        index -= keep;
+    });
+    text
+}
+
+/// How far from the left of a single line `text` the caret sits at byte index `index` of its spans joined
+/// together, measured by laying out the text before it.
+pub fn caret_offset(text: &Text, index: usize) -> f32 {crate::font::fall_back(&prefix(text, index)).size().0}
+
+/// Returns the grapheme boundary of a single line `text` nearest to `x` pixels from its left, as a byte index
+/// of its spans joined together, for placing the caret where a field is clicked.
+pub fn caret_at(text: &Text, x: f32) -> usize {
+    let joined = text.spans.iter().map(|span| span.text.as_str()).collect::<String>();
+    let boundaries = joined.grapheme_indices(true).map(|(i, _)| i).chain([joined.len()]).collect::<Vec<_>>();
+    let after = boundaries.partition_point(|i| caret_offset(text, *i) < x).min(boundaries.len() - 1);
+    match after.checked_sub(1) {
+        Some(before) if x - caret_offset(text, boundaries[before]) < caret_offset(text, boundaries[after]) - x => boundaries[before],
+        _ => boundaries[after],
+    }
+}
+
+/// The grapheme clusters of `text`, each with the byte index it starts at.
+pub fn graphemes(text: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {text.grapheme_indices(true)}
+
+/// The number of graphemes in `text`, which is what a reader counts as its length.
+pub fn grapheme_count(text: &str) -> usize {text.graphemes(true).count()}
+
+/// Returns the grapheme boundary at or before byte index `index`, so slicing there never splits a character.
+pub fn floor_boundary(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true).map(|(i, _)| i).chain([text.len()]).take_while(|i| *i <= index).last().unwrap_or(0)
+}
+
+/// Returns the grapheme boundary at or after byte index `index`.
+pub fn ceil_boundary(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true).map(|(i, _)| i).find(|i| *i >= index).unwrap_or(text.len())
+}
+
+/// Converts a count of graphemes from the start of `text` into a byte index, clamped to the end.
+pub fn byte_index(text: &str, grapheme: usize) -> usize {
+    text.grapheme_indices(true).nth(grapheme).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/// Converts byte index `index` into the number of whole graphemes before it.
+pub fn grapheme_index(text: &str, index: usize) -> usize {grapheme_count(&text[..floor_boundary(text, index)])}
+
+/// Returns the start of the word before byte index `index`, skipping any spaces and punctuation in between.
+pub fn previous_word(text: &str, index: usize) -> usize {
+    text[..floor_boundary(text, index)].unicode_word_indices().next_back().map(|(i, _)| i).unwrap_or(0)
+}
+
+/// Returns the end of the word after byte index `index`, skipping any spaces and punctuation in between.
+pub fn next_word(text: &str, index: usize) -> usize {
+    let index = ceil_boundary(text, index);
+    text[index..].unicode_word_indices().next().map(|(i, word)| index + i + word.len()).unwrap_or(text.len())
+}
+
+/// Returns the byte range of the word, run of spaces or punctuation mark under byte index `index`, for selecting
+/// it on double click.
+pub fn word_at(text: &str, index: usize) -> Range<usize> {
+    text.split_word_bound_indices().find(|(i, word)| index < i + word.len())
+        .map(|(i, word)| i..i + word.len()).unwrap_or(text.len()..text.len())
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use crate::canvas::Color;
+use crate::drawable::{Drawable, Component, SizedTree};
+use crate::event::{OnEvent, Event, TickEvent, StateChanged};
+use crate::layout::Stack;
+use crate::Context;
+
+/// The named colors of a [`Theme`].
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub surface: Color,
+    pub primary: Color,
+    /// Text and icons drawn on `primary`.
+    pub on_primary: Color,
+    pub text: Color,
+    pub text_secondary: Color,
+    pub outline: Color,
+    pub error: Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radii {
+    pub small: f32,
+    pub medium: f32,
+    pub large: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    pub size: f32,
+    pub line_height: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Typography {
+    pub title: TextStyle,
+    pub body: TextStyle,
+    pub label: TextStyle,
+    pub caption: TextStyle,
+}
+
+/// How the ring around the keyboard focused drawable is drawn, see [`Focusable`](crate::focus::Focusable).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusRing {
+    pub color: Color,
+    pub width: f32,
+    /// The gap between the focused drawable's edge and the ring.
+    pub offset: f32,
+    pub radius: f32,
+}
+
+/// The design tokens an app is styled with, stored in [`Context::state`] by [`Context::set_theme`].
+///
+/// Components read it with [`Context::theme`], or wrap a drawable in [`Themed`] to restyle it whenever the theme is swapped.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub colors: Palette,
+    /// The spacing scale, indexed by [`Theme::spacing`].
+    pub spacing: Vec<f32>,
+    pub radii: Radii,
+    pub typography: Typography,
+    pub focus_ring: FocusRing,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme::new(Palette {
+            background: Color(255, 255, 255, 255),
+            surface: Color(244, 244, 245, 255),
+            primary: Color(37, 99, 235, 255),
+            on_primary: Color(255, 255, 255, 255),
+            text: Color(24, 24, 27, 255),
+            text_secondary: Color(113, 113, 122, 255),
+            outline: Color(212, 212, 216, 255),
+            error: Color(220, 38, 38, 255),
+        })
+    }
+
+    pub fn dark() -> Self {
+        Theme::new(Palette {
+            background: Color(9, 9, 11, 255),
+            surface: Color(39, 39, 42, 255),
+            primary: Color(96, 165, 250, 255),
+            on_primary: Color(9, 9, 11, 255),
+            text: Color(250, 250, 250, 255),
+            text_secondary: Color(161, 161, 170, 255),
+            outline: Color(63, 63, 70, 255),
+            error: Color(248, 113, 113, 255),
+        })
+    }
+
+    /// Creates a theme with the given colors and the default spacing, radii, typography and focus ring.
+    pub fn new(colors: Palette) -> Self {
+        Theme {
+            focus_ring: FocusRing{color: colors.primary, width: 2.0, offset: 2.0, radius: 6.0},
+            colors,
+            spacing: vec![0.0, 4.0, 8.0, 12.0, 16.0, 24.0, 32.0, 48.0],
+            radii: Radii{small: 4.0, medium: 8.0, large: 16.0},
+            typography: Typography {
+                title: TextStyle{size: 24.0, line_height: 32.0},
+                body: TextStyle{size: 16.0, line_height: 24.0},
+                label: TextStyle{size: 14.0, line_height: 20.0},
+                caption: TextStyle{size: 12.0, line_height: 16.0},
+            },
+        }
+    }
+
+    /// Returns step `step` of the spacing scale, the largest step if it is past the end.
+    pub fn spacing(&self, step: usize) -> f32 {
+        self.spacing.get(step).or(self.spacing.last()).copied().unwrap_or_default()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {Theme::light()}
+}
+
+type Styler<D> = Arc<dyn Fn(&Theme, &mut D)>;
+
+#[derive(Clone)]
+struct Style<D>(Styler<D>);
+
+impl<D> std::fmt::Debug for Style<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Style...")
+    }
+}
+
+/// A wrapper that styles its child from the current [`Theme`] on its first frame and again whenever the theme changes.
+///
+///```rust
+/// Themed::new(Text::new(..), |theme, text| text.color = theme.colors.text)
+///```
+#[derive(Debug, Component, Clone)]
+pub struct Themed<D: Drawable + Clone + 'static>(Stack, pub D, #[skip] Style<D>, #[skip] bool);
+
+impl<D: Drawable + Clone + 'static> Themed<D> {
+    pub fn new(child: D, style: impl Fn(&Theme, &mut D) + 'static) -> Self {
+        Themed(Stack::default(), child, Style(Arc::new(style)), false)
+    }
+}
+
+impl<D: Drawable + Clone + 'static> OnEvent for Themed<D> {
+    fn on_event(&mut self, ctx: &mut Context, _sized: &SizedTree, event: Box<dyn Event>) -> Vec<Box<dyn Event>> {
+        let first = !self.3 && event.downcast_ref::<TickEvent>().is_some();
+        if first || event.downcast_ref::<StateChanged<Theme>>().is_some() {
+            self.3 = true;
+            (self.2.0)(&ctx.theme(), &mut self.1);
+        }
+        vec![event]
+    }
+}
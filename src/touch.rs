@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use crate::drawable::Offset;
+use crate::event::{Event, TouchEvent, TouchPhase};
+
+/// The fingers currently on the screen, kept in [`Context::state`](crate::Context::state) from every
+/// [`TouchEvent`] before it is dispatched.
+///
+/// Gestures read it to see fingers that have moved off the drawable they began on, which no longer pass it
+/// their [`TouchEvent`]s.
+#[derive(Debug, Clone, Default)]
+pub struct Touches(BTreeMap<u64, Offset>);
+
+impl Touches {
+    /// Each finger's id and position in logical pixels, ordered by id.
+    pub fn points(&self) -> impl Iterator<Item = (u64, Offset)> + '_ {self.0.iter().map(|(id, p)| (*id, *p))}
+
+    pub fn get(&self, id: u64) -> Option<Offset> {self.0.get(&id).copied()}
+    pub fn len(&self) -> usize {self.0.len()}
+    pub fn is_empty(&self) -> bool {self.0.is_empty()}
+
+    /// The point midway between every finger.
+    pub fn centroid(&self) -> Option<Offset> {
+        let count = self.0.len() as f32;
+        (count > 0.0).then(|| {
+            let sum = self.0.values().fold((0.0, 0.0), |sum, p| (sum.0 + p.0, sum.1 + p.1));
+            (sum.0 / count, sum.1 / count)
+        })
+    }
+
+    /// How far the fingers are from their centroid on average, which grows as they pinch apart.
+    pub fn spread(&self) -> Option<f32> {
+        let centroid = self.centroid()?;
+        Some(self.0.values().map(|p| (p.0 - centroid.0).hypot(p.1 - centroid.1)).sum::<f32>() / self.0.len() as f32)
+    }
+
+    pub(crate) fn track(&mut self, event: &dyn Event) {
+        let Some(TouchEvent{id, phase, position}) = event.downcast_ref::<TouchEvent>() else {return};
+        match (phase, position) {
+            (TouchPhase::Began | TouchPhase::Moved, Some(position)) => {self.0.insert(*id, *position);},
+            (TouchPhase::Ended | TouchPhase::Cancelled, _) => {self.0.remove(id);},
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use wasm_bindgen::{JsCast, closure::Closure};
+use wasm_bindgen_futures::{JsFuture, spawn_local};
+use web_sys::{Blob, CompositionEvent, HtmlAnchorElement, HtmlCanvasElement, HtmlInputElement, HtmlTextAreaElement, InputEvent, ResizeObserver, Url};
+
+use crate::event::{self, Event, KeyboardEvent, KeyboardState, Key, Modifiers};
+use crate::*;
+
+type Queue = Rc<RefCell<Vec<Box<dyn Event>>>>;
+
+/// A [`Handler`] for the browser that answers clipboard, file and text input calls with the Web APIs and passes
+/// every other call on to the host's own handler.
+///
+/// Answers arrive as the same events native hosts emit, so components don't change between platforms:
+/// - The async clipboard API can't be read synchronously, so [`Handler::get_clipboard`] returns `None` and every read
+///   is answered in the background with an [`event::ClipboardContents`].
+/// - [`Context::pick_file`] opens an `<input type=file>` answered with [`event::PickedFiles`], and the files
+///   stay readable through [`Context::read_file`] until the page is closed.
+/// - [`Context::save_file`] downloads the data, answered with [`event::FileSaved`] holding the name it was saved under.
+/// - [`Context::show_keyboard`] focuses a hidden text area, so the on-screen keyboard and IME composition work,
+///   and committed text arrives as [`KeyboardEvent`]s. Hosts should ignore printable keys typed into it. The area
+///   follows the [`caret`] so candidate windows open beside it.
+/// - [`Web::watch_canvas`] keeps the canvas and layout sized to the canvas's parent and sharp at the browser's
+///   `devicePixelRatio`.
+///
+/// The host passes the wrapper to [`Instance::draw`] in place of its handler and calls [`Web::deliver`] before each frame.
+pub struct Web<H: Handler> {
+    inner: H,
+    queue: Queue,
+    files: Rc<RefCell<HashMap<String, Arc<[u8]>>>>,
+    input: RefCell<Option<HtmlTextAreaElement>>,
+    /// The canvas's latest size in CSS pixels and `devicePixelRatio`, until the next frame picks it up.
+    viewport: Rc<Cell<Option<((f32, f32), f32)>>>,
+}
+
+impl<H: Handler> Web<H> {
+    pub fn new(inner: H) -> Self {
+        Web{inner, queue: Queue::default(), files: Rc::default(), input: RefCell::new(None), viewport: Rc::default()}
+    }
+
+    pub fn inner(&self) -> &H {&self.inner}
+
+    /// Hands the answers that arrived since the last frame to `instance`, along with any change to the canvas's size or density.
+    pub fn deliver(&self, instance: &mut Instance) {
+        if let Some((size, scale)) = self.viewport.take() {
+            instance.set_scale_factor(scale);
+            if size != instance.screen {instance.resize(size);}
+        }
+        instance.events.extend(self.queue.borrow_mut().drain(..));
+    }
+
+    /// Sizes `canvas` to fill its parent element and follows the parent's size and the browser's `devicePixelRatio`,
+    /// which changes with zoom and when the window moves between displays.
+    ///
+    /// The canvas's CSS size is pinned to the parent's and its backing store kept at that size times the ratio, so
+    /// the backing store never feeds back into the size measured. The host should resize its surface to
+    /// [`Instance::physical_size`] after [`Web::deliver`], while layout sees the CSS size as logical pixels.
+    pub fn watch_canvas(&self, canvas: &HtmlCanvasElement) {
+        let (viewport, target) = (self.viewport.clone(), canvas.clone());
+        let container = canvas.parent_element();
+        let measured = container.clone();
+        let measure = move || {
+            let Some(window) = web_sys::window() else {return};
+            let rect = match &measured {
+                Some(parent) => parent.get_bounding_client_rect(),
+                None => target.get_bounding_client_rect(),
+            };
+            let (size, scale) = ((rect.width() as f32, rect.height() as f32), window.device_pixel_ratio() as f32);
+            let style = target.style();
+            let _ = style.set_property("width", &format!("{}px", size.0));
+            let _ = style.set_property("height", &format!("{}px", size.1));
+            target.set_width((size.0 * scale).round() as u32);
+            target.set_height((size.1 * scale).round() as u32);
+            viewport.set(Some((size, scale)));
+        };
+        measure();
+
+        let resized = Closure::<dyn FnMut()>::new(measure);
+        if let Ok(observer) = ResizeObserver::new(resized.as_ref().unchecked_ref()) {
+            match &container {
+                Some(parent) => observer.observe(parent),
+                None => observer.observe(canvas),
+            }
+        }
+        if let Some(window) = web_sys::window() {
+            let _ = window.add_event_listener_with_callback("resize", resized.as_ref().unchecked_ref());
+        }
+        resized.forget();
+    }
+
+    fn document() -> Option<web_sys::Document> {web_sys::window()?.document()}
+
+    /// The hidden text area that receives text input, created the first time the keyboard is shown.
+    fn text_area(&self) -> Option<HtmlTextAreaElement> {
+        if let Some(input) = self.input.borrow().as_ref() {return Some(input.clone());}
+        let document = Self::document()?;
+        let input = document.create_element("textarea").ok()?.dyn_into::<HtmlTextAreaElement>().ok()?;
+        let style = input.style();
+        for (property, value) in [("position", "fixed"), ("left", "0"), ("bottom", "0"), ("opacity", "0"), ("width", "1px"), ("height", "1px")] {
+            let _ = style.set_property(property, value);
+        }
+        input.set_attribute("autocapitalize", "off").ok()?;
+        document.body()?.append_child(&input).ok()?;
+
+        let (queue, area) = (self.queue.clone(), input.clone());
+        let composed = Closure::<dyn FnMut(CompositionEvent)>::new(move |e: CompositionEvent| {
+            type_text(&queue, &e.data().unwrap_or_default());
+            area.set_value("");
+        });
+        let (queue, area) = (self.queue.clone(), input.clone());
+        let typed = Closure::<dyn FnMut(InputEvent)>::new(move |e: InputEvent| {
+            if e.is_composing() {return;}
+            match e.input_type().as_str() {
+                "deleteContentBackward" => type_key(&queue, Key::Backspace),
+                "deleteContentForward" => type_key(&queue, Key::Delete),
+                "insertLineBreak" => type_key(&queue, Key::Enter),
+                _ => type_text(&queue, &e.data().unwrap_or_default()),
+            }
+            area.set_value("");
+        });
+        input.add_event_listener_with_callback("compositionend", composed.as_ref().unchecked_ref()).ok()?;
+        input.add_event_listener_with_callback("input", typed.as_ref().unchecked_ref()).ok()?;
+        composed.forget();
+        typed.forget();
+
+        *self.input.borrow_mut() = Some(input.clone());
+        Some(input)
+    }
+}
+
+/// Queues a press and release of `key`, as a key typed into the text area.
+fn type_key(queue: &Queue, key: Key) {
+    queue.borrow_mut().extend([KeyboardState::Pressed, KeyboardState::Released].map(|state| {
+        Box::new(KeyboardEvent{key, state, modifiers: Modifiers::default()}) as Box<dyn Event>
+    }));
+}
+
+fn type_text(queue: &Queue, text: &str) {
+    text.chars().for_each(|c| type_key(queue, match c {'\n' => Key::Enter, ' ' => Key::Space, c => Key::Character(c)}));
+}
+
+impl<H: Handler> Handler for Web<H> {
+    fn air(&self) -> &air::Context {self.inner.air()}
+    fn check_permission(&self, permission: Permission) -> PermissionStatus {self.inner.check_permission(permission)}
+    fn request_permission(&self, permission: Permission) {self.inner.request_permission(permission)}
+    fn start_camera(&self, settings: FrameSettings) -> Box<dyn Camera> {self.inner.start_camera(settings)}
+    fn cameras(&self) -> Vec<CameraInfo> {self.inner.cameras()}
+    fn select_camera(&self, facing: Facing) {self.inner.select_camera(facing)}
+    fn set_torch(&self, on: bool) {self.inner.set_torch(on)}
+    fn pick_photo(&self, options: PhotoOptions) {self.inner.pick_photo(options)}
+    fn capture_screen(&self, target: CaptureTarget) {self.inner.capture_screen(target)}
+
+    fn get_safe_area(&self) -> (f32, f32, f32, f32) {self.inner.get_safe_area()}
+    fn get_appearance(&self) -> Appearance {self.inner.get_appearance()}
+    fn get_locale(&self) -> Locale {self.inner.get_locale()}
+    fn get_power_state(&self) -> PowerState {self.inner.get_power_state()}
+    fn get_network_status(&self) -> NetworkStatus {self.inner.get_network_status()}
+    fn share_social(&self, data: String) {self.inner.share_social(data)}
+    fn open_url(&self, url: String) {self.inner.open_url(url)}
+
+    fn system_font(&self, family: String) -> Option<Vec<u8>> {self.inner.system_font(family)}
+
+    fn set_clipboard(&self, data: String) {
+        let Some(window) = web_sys::window() else {return};
+        let promise = window.navigator().clipboard().write_text(&data);
+        spawn_local(async move {let _ = JsFuture::from(promise).await;});
+    }
+
+    fn get_clipboard(&self) -> Option<String> {
+        self.request_clipboard();
+        None
+    }
+
+    fn request_clipboard(&self) -> bool {
+        let queue = self.queue.clone();
+        let promise = web_sys::window().map(|window| window.navigator().clipboard().read_text());
+        spawn_local(async move {
+            let text = match promise {
+                Some(promise) => JsFuture::from(promise).await.ok().and_then(|text| text.as_string()).filter(|text| !text.is_empty()),
+                None => None,
+            };
+            queue.borrow_mut().push(Box::new(event::ClipboardContents(text)));
+        });
+        true
+    }
+
+    fn trigger_haptic(&self, haptic: Haptic) {self.inner.trigger_haptic(haptic)}
+
+    fn show_keyboard(&self, kind: KeyboardKind) {
+        let Some(input) = self.text_area() else {return};
+        let mode = match kind {
+            KeyboardKind::Text => "text",
+            KeyboardKind::Numeric => "numeric",
+            KeyboardKind::Decimal => "decimal",
+            KeyboardKind::Email => "email",
+            KeyboardKind::Url => "url",
+            KeyboardKind::Phone => "tel",
+        };
+        let _ = input.set_attribute("inputmode", mode);
+        let _ = input.focus();
+    }
+
+    fn hide_keyboard(&self) {
+        if let Some(input) = self.input.borrow().as_ref() {let _ = input.blur();}
+    }
+
+    fn set_caret_rect(&self, rect: Option<drawable::Rect>) {
+        let (Some((x, y, _, h)), Some(input)) = (rect, self.input.borrow().clone()) else {return};
+        let style = input.style();
+        let _ = style.set_property("left", &format!("{x}px"));
+        let _ = style.set_property("top", &format!("{}px", y + h));
+        let _ = style.remove_property("bottom");
+    }
+
+    fn set_cursor_visible(&self, visible: bool) {
+        let Some(body) = Self::document().and_then(|document| document.body()) else {return};
+        let _ = body.style().set_property("cursor", if visible {"auto"} else {"none"});
+    }
+
+    fn lock_orientation(&self, orientation: ScreenOrientation) {self.inner.lock_orientation(orientation)}
+    fn unlock_orientation(&self) {self.inner.unlock_orientation()}
+
+    fn drag_window(&self) {self.inner.drag_window()}
+    fn resize_window(&self, edge: ResizeEdge) {self.inner.resize_window(edge)}
+    fn minimize_window(&self) {self.inner.minimize_window()}
+    fn maximize_window(&self, maximized: bool) {self.inner.maximize_window(maximized)}
+    fn set_fullscreen(&self, fullscreen: bool) {
+        let Some(document) = Self::document() else {return};
+        match (fullscreen, document.document_element()) {
+            (true, Some(element)) => {let _ = element.request_fullscreen();},
+            (false, _) => document.exit_fullscreen(),
+            _ => {}
+        }
+    }
+    fn set_always_on_top(&self, on_top: bool) {self.inner.set_always_on_top(on_top)}
+    fn window_state(&self) -> WindowState {
+        let fullscreen = Self::document().is_some_and(|document| document.fullscreen_element().is_some());
+        WindowState{fullscreen, ..self.inner.window_state()}
+    }
+    fn close_window(&self) {self.inner.close_window()}
+    fn set_window_title(&self, title: String) {
+        if let Some(document) = Self::document() {document.set_title(&title);}
+    }
+    fn set_window_icon(&self, icon: image::RgbaImage) {self.inner.set_window_icon(icon)}
+
+    fn notify(&self, notification: Notification) {self.inner.notify(notification)}
+    fn cancel_notification(&self, id: String) {self.inner.cancel_notification(id)}
+
+    fn pick_file(&self, filters: Vec<FileFilter>, multiple: bool) {
+        let Some(input) = Self::document().and_then(|document| document.create_element("input").ok())
+            .and_then(|element| element.dyn_into::<HtmlInputElement>().ok()) else {return};
+        input.set_type("file");
+        input.set_multiple(multiple);
+        input.set_accept(&filters.iter().flat_map(|filter| filter.extensions.iter().map(|ext| format!(".{ext}"))).collect::<Vec<_>>().join(","));
+
+        let (queue, files, picker) = (self.queue.clone(), self.files.clone(), input.clone());
+        let changed = Closure::<dyn FnMut()>::new(move || {
+            let list = picker.files();
+            let chosen = (0..list.as_ref().map(|list| list.length()).unwrap_or(0)).filter_map(|i| list.as_ref()?.get(i)).collect::<Vec<_>>();
+            let (queue, files) = (queue.clone(), files.clone());
+            spawn_local(async move {
+                let mut picked = vec![];
+                for file in chosen {
+                    let Ok(buffer) = JsFuture::from(file.array_buffer()).await else {continue};
+                    let data: Arc<[u8]> = js_sys::Uint8Array::new(&buffer).to_vec().into();
+                    let handle = FileHandle(format!("web:{}", uuid::Uuid::new_v4()));
+                    files.borrow_mut().insert(handle.0.clone(), data.clone());
+                    picked.push(event::PickedFile{name: file.name(), handle, data});
+                }
+                queue.borrow_mut().push(Box::new(event::PickedFiles(picked)));
+            });
+        });
+        let queue = self.queue.clone();
+        let cancelled = Closure::<dyn FnMut()>::new(move || queue.borrow_mut().push(Box::new(event::PickedFiles(vec![]))));
+        input.set_onchange(Some(changed.as_ref().unchecked_ref()));
+        let _ = input.add_event_listener_with_callback("cancel", cancelled.as_ref().unchecked_ref());
+        changed.forget();
+        cancelled.forget();
+        input.click();
+    }
+
+    fn save_file(&self, suggested_name: String, data: Vec<u8>) {
+        let saved = (|| {
+            let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(&data[..]));
+            let url = Url::create_object_url_with_blob(&Blob::new_with_u8_array_sequence(&parts).ok()?).ok()?;
+            let anchor = Self::document()?.create_element("a").ok()?.dyn_into::<HtmlAnchorElement>().ok()?;
+            anchor.set_href(&url);
+            anchor.set_download(&suggested_name);
+            anchor.click();
+            let _ = Url::revoke_object_url(&url);
+            Some(FileHandle(suggested_name.clone()))
+        })();
+        if let Some(handle) = &saved {self.files.borrow_mut().insert(handle.0.clone(), data.into());}
+        self.queue.borrow_mut().push(Box::new(event::FileSaved(saved)));
+    }
+
+    fn read_file(&self, handle: FileHandle) -> Option<Vec<u8>> {
+        self.files.borrow().get(&handle.0).map(|data| data.to_vec()).or_else(|| self.inner.read_file(handle))
+    }
+
+    fn ble_scan(&self, services: Vec<uuid::Uuid>) {self.inner.ble_scan(services)}
+    fn ble_stop_scan(&self) {self.inner.ble_stop_scan()}
+    fn ble_connect(&self, device: bluetooth::Device) {self.inner.ble_connect(device)}
+    fn ble_disconnect(&self, device: bluetooth::Device) {self.inner.ble_disconnect(device)}
+    fn ble_read(&self, characteristic: bluetooth::Characteristic) {self.inner.ble_read(characteristic)}
+    fn ble_write(&self, characteristic: bluetooth::Characteristic, data: Vec<u8>) {self.inner.ble_write(characteristic, data)}
+    fn ble_subscribe(&self, characteristic: bluetooth::Characteristic, subscribe: bool) {self.inner.ble_subscribe(characteristic, subscribe)}
+
+    fn get_location(&self) {self.inner.get_location()}
+    fn watch_location(&self, interval: std::time::Duration) {self.inner.watch_location(interval)}
+    fn stop_location(&self) {self.inner.stop_location()}
+
+    fn set_cloud(&self, key: String, value: String) {self.inner.set_cloud(key, value)}
+    fn get_cloud(&self, key: String) -> Option<String> {self.inner.get_cloud(key)}
+    fn remove_cloud(&self, key: String) {self.inner.remove_cloud(key)}
+    fn set_cloud_batch(&self, values: Vec<(String, String)>) {self.inner.set_cloud_batch(values)}
+    fn get_cloud_batch(&self, keys: Vec<String>) -> Vec<Option<String>> {self.inner.get_cloud_batch(keys)}
+    fn list_cloud(&self, prefix: String) -> Vec<String> {self.inner.list_cloud(prefix)}
+
+    fn set_secure(&self, key: String, value: String) {self.inner.set_secure(key, value)}
+    fn get_secure(&self, key: String) -> Option<String> {self.inner.get_secure(key)}
+    fn remove_secure(&self, key: String) {self.inner.remove_secure(key)}
+
+    fn call_service(&self, id: uuid::Uuid, name: String, payload: String) {self.inner.call_service(id, name, payload)}
+}